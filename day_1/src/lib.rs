@@ -1,23 +1,138 @@
-use std::collections::LinkedList;
-use std::fs;
+use std::{fmt, fs, mem};
 
-pub fn get_highest_calories(path: &str) -> u32 {
+/// Loads the total calories carried by every elf, in the order they appear in the file.
+pub fn load_calories(path: &str) -> Vec<u32> {
     let data = fs::read_to_string(path).expect("Unable to read file");
-    let mut calories = LinkedList::<u32>::new();
-    calories.push_back(0);
-    data.split('\n').for_each(|s| {
-        if s.is_empty() {
-            calories.push_back(0);
+    let mut calories = vec![];
+    let mut current: Option<u32> = None;
+    data.lines().for_each(|s| {
+        if s.trim().is_empty() {
+            calories.extend(current.take());
         } else {
-            *calories.back_mut().unwrap() += s.trim().parse().unwrap_or(0);
+            *current.get_or_insert(0) += s.trim().parse().unwrap_or(0);
         }
     });
+    calories.extend(current);
+    calories
+}
 
-    let mut calories: Vec<_> = calories.into_iter().collect();
+pub fn get_highest_calories(path: &str) -> u32 {
+    let mut calories = load_calories(path);
     calories.sort();
     calories.into_iter().rev().take(3).sum()
 }
 
+/// Returns the smallest set of elves whose calories sum up to at least `target`.
+///
+/// Picking the elves carrying the most first always gives the minimal count.
+/// Returns `None` if even all elves together don't reach the target.
+pub fn min_elves_for_target(calories: &[u32], target: u32) -> Option<Vec<usize>> {
+    let mut order: Vec<usize> = (0..calories.len()).collect();
+    order.sort_by(|a, b| calories[*b].cmp(&calories[*a]));
+
+    let mut chosen = vec![];
+    let mut sum = 0u64;
+    for elf in order {
+        if sum >= target as u64 {
+            break;
+        }
+        sum += calories[elf] as u64;
+        chosen.push(elf);
+    }
+
+    if sum >= target as u64 {
+        Some(chosen)
+    } else {
+        None
+    }
+}
+
+/// Returns the elves needed to feed a team of `team_size` members eating
+/// `calories_per_member` each.
+pub fn elves_to_feed_team(
+    calories: &[u32],
+    team_size: u32,
+    calories_per_member: u32,
+) -> Option<Vec<usize>> {
+    let target = team_size.checked_mul(calories_per_member)?;
+    min_elves_for_target(calories, target)
+}
+
+/// Most memory in bytes `exact_elves_for_target` allocates for its tables, 256 MiB.
+pub const MAX_EXACT_BYTES: usize = 1 << 28;
+
+/// Exact search which would need more than `MAX_EXACT_BYTES` of memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooLarge;
+
+impl fmt::Display for TooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "target too large to search for an exact sum")
+    }
+}
+
+impl std::error::Error for TooLarge {}
+
+/// Returns the smallest set of elves whose calories sum up to exactly `target`, or `None` if
+/// there is no such set.
+///
+/// Runs in `O(elves * target)` time and memory so it's meant for small targets. Fails with
+/// `TooLarge` without searching if the tables would take more than `MAX_EXACT_BYTES`.
+pub fn exact_elves_for_target(
+    calories: &[u32],
+    target: u32,
+) -> Result<Option<Vec<usize>>, TooLarge> {
+    let total: u64 = calories.iter().map(|&c| c as u64).sum();
+    if total < target as u64 {
+        return Ok(None);
+    }
+    let target = target as usize;
+    // One flag per elf and one optional count for every sum up to the target
+    let bytes = calories
+        .len()
+        .checked_add(mem::size_of::<Option<u32>>())
+        .and_then(|per_sum| per_sum.checked_mul(target + 1));
+    if bytes.is_none_or(|b| b > MAX_EXACT_BYTES) {
+        return Err(TooLarge);
+    }
+    // best[s] holds the minimal number of elves summing to s, using the elves seen so far
+    let mut best: Vec<Option<u32>> = vec![None; target + 1];
+    best[0] = Some(0);
+    // taken[elf][s] records whether elf was used to reach s in the optimal solution
+    let mut taken = vec![vec![false; target + 1]; calories.len()];
+
+    for (elf, &c) in calories.iter().enumerate() {
+        let c = c as usize;
+        if c > target {
+            continue;
+        }
+        for s in (c..=target).rev() {
+            let Some(count) = best[s - c] else {
+                continue;
+            };
+            if best[s].is_none_or(|b| count + 1 < b) {
+                best[s] = Some(count + 1);
+                taken[elf][s] = true;
+            }
+        }
+    }
+
+    if best[target].is_none() {
+        return Ok(None);
+    }
+
+    let mut chosen = vec![];
+    let mut s = target;
+    for elf in (0..calories.len()).rev() {
+        if s > 0 && taken[elf][s] {
+            chosen.push(elf);
+            s -= calories[elf] as usize;
+        }
+    }
+    chosen.reverse();
+    Ok(Some(chosen))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -33,4 +148,54 @@ mod tests {
         let calories = get_highest_calories("input.txt");
         assert_eq!(calories, 201524);
     }
+
+    #[test]
+    fn per_elf_totals() {
+        let calories = load_calories("test_data.txt");
+        assert_eq!(calories, vec![6000, 4000, 11000, 24000, 10000]);
+    }
+
+    #[test]
+    fn min_elves() {
+        let calories = load_calories("test_data.txt");
+        assert_eq!(min_elves_for_target(&calories, 0), Some(vec![]));
+        assert_eq!(min_elves_for_target(&calories, 30000), Some(vec![3, 2]));
+        assert_eq!(
+            min_elves_for_target(&calories, 55000),
+            Some(vec![3, 2, 4, 0, 1])
+        );
+        assert_eq!(min_elves_for_target(&calories, 55001), None);
+    }
+
+    #[test]
+    fn feed_team() {
+        let calories = load_calories("test_data.txt");
+        assert_eq!(elves_to_feed_team(&calories, 3, 8000), Some(vec![3]));
+        assert_eq!(elves_to_feed_team(&calories, 4, 8000), Some(vec![3, 2]));
+        assert_eq!(elves_to_feed_team(&calories, u32::MAX, 2), None);
+    }
+
+    #[test]
+    fn exact_elves() {
+        let calories = load_calories("test_data.txt");
+        let exact = |target| exact_elves_for_target(&calories, target).unwrap();
+        assert_eq!(exact(0), Some(vec![]));
+        assert_eq!(exact(10000), Some(vec![4]));
+        assert_eq!(exact(20000), Some(vec![0, 1, 4]));
+        assert_eq!(exact(21000), Some(vec![2, 4]));
+        assert_eq!(exact(5000), None);
+    }
+
+    #[test]
+    fn exact_target_too_large() {
+        // Unreachable targets are answered without searching
+        assert_eq!(exact_elves_for_target(&[1, 2], u32::MAX), Ok(None));
+        assert_eq!(
+            exact_elves_for_target(&[u32::MAX, 1], u32::MAX),
+            Err(TooLarge)
+        );
+        // The table of counts alone is over the limit even for a single elf
+        let target = (MAX_EXACT_BYTES / 8) as u32;
+        assert_eq!(exact_elves_for_target(&[target], target), Err(TooLarge));
+    }
 }
@@ -0,0 +1,139 @@
+use crate::{Outcome, Round, Shape, Strategy};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundReport {
+    pub opponent: Shape,
+    pub response: Shape,
+    pub outcome: Outcome,
+    pub shape_bonus: u32,
+    pub outcome_score: u32,
+    /// Score of all rounds up to and including this one
+    pub running_total: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub total: u32,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    pub longest_win_streak: usize,
+    pub longest_draw_streak: usize,
+    pub longest_loss_streak: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breakdown {
+    pub rounds: Vec<RoundReport>,
+    pub summary: Summary,
+}
+
+impl RoundReport {
+    pub fn score(&self) -> u32 {
+        self.shape_bonus + self.outcome_score
+    }
+}
+
+/// Scores every round separately so the final sum can be audited.
+pub fn breakdown(rounds: &[Round], strategy: &impl Strategy) -> Breakdown {
+    let mut running_total = 0;
+    let reports: Vec<RoundReport> = rounds
+        .iter()
+        .map(|r| {
+            let response = strategy.respond(r);
+            let outcome = response.against(r.opponent);
+            running_total += r.calc_score(strategy);
+            RoundReport {
+                opponent: r.opponent,
+                response,
+                outcome,
+                shape_bonus: response.bonus(),
+                outcome_score: outcome.score(),
+                running_total,
+            }
+        })
+        .collect();
+
+    let mut summary = Summary {
+        total: running_total,
+        ..Default::default()
+    };
+    let mut streak = 0;
+    for (i, report) in reports.iter().enumerate() {
+        if i > 0 && reports[i - 1].outcome == report.outcome {
+            streak += 1;
+        } else {
+            streak = 1;
+        }
+        let (count, longest) = match report.outcome {
+            Outcome::Win => (&mut summary.wins, &mut summary.longest_win_streak),
+            Outcome::Draw => (&mut summary.draws, &mut summary.longest_draw_streak),
+            Outcome::Lose => (&mut summary.losses, &mut summary.longest_loss_streak),
+        };
+        *count += 1;
+        *longest = (*longest).max(streak);
+    }
+
+    Breakdown {
+        rounds: reports,
+        summary,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{load_rounds, parse_rounds, ColumnAsOutcome, ColumnAsShape};
+
+    #[test]
+    fn example() {
+        let rounds = load_rounds("test_data.txt").unwrap();
+        let breakdown = breakdown(&rounds, &ColumnAsShape);
+
+        let scores: Vec<_> = breakdown.rounds.iter().map(|r| r.score()).collect();
+        assert_eq!(scores, vec![8, 1, 6]);
+        let totals: Vec<_> = breakdown.rounds.iter().map(|r| r.running_total).collect();
+        assert_eq!(totals, vec![8, 9, 15]);
+        assert_eq!(
+            breakdown.rounds[1],
+            RoundReport {
+                opponent: Shape::Paper,
+                response: Shape::Rock,
+                outcome: Outcome::Lose,
+                shape_bonus: 1,
+                outcome_score: 0,
+                running_total: 9,
+            }
+        );
+        assert_eq!(
+            breakdown.summary,
+            Summary {
+                total: 15,
+                wins: 1,
+                draws: 1,
+                losses: 1,
+                longest_win_streak: 1,
+                longest_draw_streak: 1,
+                longest_loss_streak: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn streaks() {
+        let rounds = parse_rounds("A Z\nB Z\nC Y\nA Z\nB Z\nC Z\nA X\nA X".as_bytes()).unwrap();
+        let summary = breakdown(&rounds, &ColumnAsOutcome).summary;
+        assert_eq!((summary.wins, summary.draws, summary.losses), (5, 1, 2));
+        assert_eq!(summary.longest_win_streak, 3);
+        assert_eq!(summary.longest_draw_streak, 1);
+        assert_eq!(summary.longest_loss_streak, 2);
+    }
+
+    #[test]
+    fn input_total_matches() {
+        let rounds = load_rounds("input.txt").unwrap();
+        let summary = breakdown(&rounds, &ColumnAsOutcome).summary;
+        assert_eq!(summary.total, 14652);
+        assert_eq!(summary.wins + summary.draws + summary.losses, rounds.len());
+    }
+}
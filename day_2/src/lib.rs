@@ -1,150 +1,235 @@
+pub mod breakdown;
+pub mod notation;
+pub mod optimiser;
+pub mod rules;
+pub mod tournament;
+
 use std::{
+    fmt,
     fs::File,
     io::{BufRead, BufReader},
+    str::FromStr,
+    sync::LazyLock,
 };
 
 use anyhow::Result;
 
-enum Outcome {
+use notation::Notation;
+use rules::Rules;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
     Lose,
     Draw,
     Win,
 }
 
-struct Round {
-    shape_bonus: u32,
-    outcome: Outcome,
+/// Second column of the strategy guide. Its meaning depends on the `Strategy` used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    X,
+    Y,
+    Z,
 }
 
-impl Round {
-    fn calc_score(&self) -> u32 {
-        self.shape_bonus + self.outcome.score()
-    }
+/// Round of the classic three shape game. Guides for other `rules::Rules` are read into
+/// `rules::RulesRound`s instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Round {
+    pub opponent: Shape,
+    pub column: Column,
 }
 
-impl Outcome {
-    fn score(&self) -> u32 {
+/// Decides which shape to play in a round of the strategy guide.
+pub trait Strategy {
+    fn respond(&self, round: &Round) -> Shape;
+}
+
+/// First part: `X`, `Y` and `Z` are Rock, Paper and Scissors.
+pub struct ColumnAsShape;
+
+/// Second part: `X`, `Y` and `Z` mean we need to lose, draw or win.
+pub struct ColumnAsOutcome;
+
+/// Rules every `Shape` is played by. Their shapes are listed in the order of `Shape::ALL`, so
+/// `Shape::id` is the id in the table.
+static CLASSIC: LazyLock<Rules> = LazyLock::new(Rules::classic);
+
+impl Shape {
+    pub const ALL: [Shape; 3] = [Shape::Rock, Shape::Paper, Shape::Scissors];
+
+    /// Index of the shape in `Shape::ALL`
+    pub fn id(&self) -> usize {
         match self {
-            Self::Lose => 0,
-            Self::Draw => 3,
-            Self::Win => 6,
+            Self::Rock => 0,
+            Self::Paper => 1,
+            Self::Scissors => 2,
         }
     }
+
+    /// Shape to play against `opponent` to get the `outcome`
+    pub fn for_outcome(opponent: Shape, outcome: Outcome) -> Shape {
+        let response = CLASSIC
+            .responses(opponent.id(), outcome)
+            .next()
+            .expect("Classic rules have one shape for every outcome");
+        Self::ALL[response]
+    }
+
+    /// Shape defeated by this one
+    pub fn beats(&self) -> Shape {
+        Self::for_outcome(*self, Outcome::Lose)
+    }
+
+    /// Shape defeating this one
+    pub fn beaten_by(&self) -> Shape {
+        Self::for_outcome(*self, Outcome::Win)
+    }
+
+    pub fn bonus(&self) -> u32 {
+        CLASSIC.bonus(self.id())
+    }
+
+    /// Outcome of playing this shape against `opponent`
+    pub fn against(&self, opponent: Shape) -> Outcome {
+        CLASSIC.outcome(self.id(), opponent.id())
+    }
+
+    /// Score of playing this shape against `opponent`
+    pub fn score_against(&self, opponent: Shape) -> u32 {
+        CLASSIC.score(self.id(), opponent.id())
+    }
 }
 
 impl Outcome {
-    fn new(s: &str) -> Self {
-        if s.ends_with('X') {
-            Outcome::Lose
-        } else if s.ends_with('Y') {
-            Outcome::Draw
-        } else {
-            Outcome::Win
+    pub fn score(&self) -> u32 {
+        CLASSIC.outcome_score(*self)
+    }
+}
+
+impl Strategy for ColumnAsShape {
+    fn respond(&self, round: &Round) -> Shape {
+        match round.column {
+            Column::X => Shape::Rock,
+            Column::Y => Shape::Paper,
+            Column::Z => Shape::Scissors,
         }
     }
 }
 
+impl Strategy for ColumnAsOutcome {
+    fn respond(&self, round: &Round) -> Shape {
+        let outcome = match round.column {
+            Column::X => Outcome::Lose,
+            Column::Y => Outcome::Draw,
+            Column::Z => Outcome::Win,
+        };
+        Shape::for_outcome(round.opponent, outcome)
+    }
+}
+
 impl Round {
-    fn new(s: &str) -> Self {
-        let outcome = Outcome::new(s);
-        let shape_bonus = Round::calc_shape_bonus(s, &outcome);
-        Self {
-            shape_bonus,
-            outcome,
+    pub fn calc_score(&self, strategy: &impl Strategy) -> u32 {
+        strategy.respond(self).score_against(self.opponent)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// Line doesn't consist of exactly two symbols
+    Malformed(String),
+    UnknownOpponent(String),
+    UnknownColumn(String),
+    /// Column mapping declared in the header can't be understood
+    InvalidHeader(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line number counted from 1
+    pub line: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(l) => write!(f, "expected two symbols, got {:?}", l),
+            Self::UnknownOpponent(s) => write!(f, "unknown opponent shape {:?}", s),
+            Self::UnknownColumn(s) => write!(f, "unknown column {:?}", s),
+            Self::InvalidHeader(s) => write!(f, "invalid header: {}", s),
         }
     }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Puzzle notation shared by every parsed round
+static LETTERS: LazyLock<Notation> = LazyLock::new(Notation::letters);
+
+impl FromStr for Round {
+    type Err = ParseErrorKind;
+
+    /// Parses `A X` style round. Whitespace around and between the symbols is ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LETTERS.parse_round(s)
+    }
+}
 
-    fn calc_shape_bonus(s: &str, outcome: &Outcome) -> u32 {
-        match outcome {
-            Outcome::Lose => {
-                if s.starts_with('A') {
-                    3
-                } else if s.starts_with('B') {
-                    1
-                } else {
-                    2
-                }
-            }
-            Outcome::Draw => {
-                if s.starts_with('A') {
-                    1
-                } else if s.starts_with('B') {
-                    2
-                } else {
-                    3
-                }
-            }
-            Outcome::Win => {
-                if s.starts_with('A') {
-                    2
-                } else if s.starts_with('B') {
-                    3
-                } else {
-                    1
-                }
-            }
+/// Parses strategy guide skipping blank lines.
+pub fn parse_rounds(reader: impl BufRead) -> Result<Vec<Round>> {
+    let mut rounds = vec![];
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
         }
+        let round = line
+            .parse()
+            .map_err(|kind| ParseError { line: i + 1, kind })?;
+        rounds.push(round);
     }
+    Ok(rounds)
 }
 
-pub fn calc_score(file: &str) -> Result<u32> {
+pub fn load_rounds(file: &str) -> Result<Vec<Round>> {
     let file = File::open(file)?;
-    let reader = BufReader::new(file);
-    let mut score = 0;
-    reader
-        .lines()
-        .filter_map(|l| l.ok())
-        .map(|l| Round::new(l.as_str()))
-        .for_each(|r| score += r.calc_score());
+    parse_rounds(BufReader::new(file))
+}
 
-    Ok(score)
+pub fn score_guide(file: &str, strategy: &impl Strategy) -> Result<u32> {
+    Ok(load_rounds(file)?
+        .iter()
+        .map(|r| r.calc_score(strategy))
+        .sum())
+}
+
+// Second part
+pub fn calc_score(file: &str) -> Result<u32> {
+    score_guide(file, &ColumnAsOutcome)
 }
 
-// ================================
 // First part
 pub fn load_data(file: &str) -> Result<u32> {
-    let file = File::open(file)?;
-    let reader = BufReader::new(file);
-    let mut score = 0;
-    reader
-        .lines()
-        .filter_map(|l| l.ok())
-        .for_each(|l| score += round_score(&l));
-    Ok(score)
-}
-
-fn round_score(line: &str) -> u32 {
-    if line.starts_with('A') {
-        if line.ends_with('X') {
-            return 4;
-        } else if line.ends_with('Y') {
-            return 8;
-        } else {
-            return 3;
-        }
-    } else if line.starts_with('B') {
-        if line.ends_with('X') {
-            return 1;
-        } else if line.ends_with('Y') {
-            return 5;
-        } else {
-            return 9;
-        }
-    } else {
-        if line.ends_with('X') {
-            return 7;
-        } else if line.ends_with('Y') {
-            return 2;
-        } else {
-            return 6;
-        }
-    }
+    score_guide(file, &ColumnAsShape)
 }
-// ===================================
 
 #[cfg(test)]
 mod tests {
-    use crate::{calc_score, load_data};
+    use super::*;
 
     #[test]
     fn test_data() {
@@ -169,4 +254,59 @@ mod tests {
         let score = calc_score("input.txt").unwrap();
         assert_eq!(score, 14652)
     }
+
+    #[test]
+    fn shapes_beat_each_other_in_a_cycle() {
+        assert_eq!(
+            Shape::ALL.map(|s| s.beats()),
+            [Shape::Scissors, Shape::Rock, Shape::Paper]
+        );
+        for shape in Shape::ALL {
+            assert_eq!(shape.beats().beaten_by(), shape);
+            assert_eq!(shape.against(shape.beats()), Outcome::Win);
+            assert_eq!(shape.against(shape.beaten_by()), Outcome::Lose);
+            assert_eq!(shape.against(shape), Outcome::Draw);
+        }
+    }
+
+    #[test]
+    fn strategies() {
+        let round: Round = "A Y".parse().unwrap();
+        assert_eq!(ColumnAsShape.respond(&round), Shape::Paper);
+        assert_eq!(ColumnAsOutcome.respond(&round), Shape::Rock);
+        assert_eq!(round.calc_score(&ColumnAsShape), 8);
+        assert_eq!(round.calc_score(&ColumnAsOutcome), 4);
+    }
+
+    #[test]
+    fn surrounding_whitespace_is_ignored() {
+        let rounds = parse_rounds(" A Y \r\n\nB\tX\n  C   Z\n\n".as_bytes()).unwrap();
+        assert_eq!(rounds, parse_rounds("A Y\nB X\nC Z".as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn invalid_lines_are_rejected() {
+        let cases = [
+            (
+                "A Y\nD X",
+                2,
+                ParseErrorKind::UnknownOpponent("D".to_owned()),
+            ),
+            ("A W", 1, ParseErrorKind::UnknownColumn("W".to_owned())),
+            (
+                "A Y\nB X\nAX",
+                3,
+                ParseErrorKind::Malformed("AX".to_owned()),
+            ),
+            ("A Y Z", 1, ParseErrorKind::Malformed("A Y Z".to_owned())),
+            ("a x", 1, ParseErrorKind::UnknownOpponent("a".to_owned())),
+        ];
+        for (input, line, kind) in cases {
+            let err = parse_rounds(input.as_bytes()).unwrap_err();
+            assert_eq!(
+                err.downcast::<ParseError>().unwrap(),
+                ParseError { line, kind }
+            );
+        }
+    }
 }
@@ -0,0 +1,274 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use anyhow::Result;
+
+use crate::{Column, ParseError, ParseErrorKind, Round, Shape};
+
+/// Maps symbols of a guide to opponent shapes and second column values.
+///
+/// Second column written as a shape is translated the way the puzzle encodes it: Rock is `X`,
+/// Paper is `Y` and Scissors is `Z`, so both `Strategy` implementations work for every notation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notation {
+    opponent: Vec<(String, Shape)>,
+    column: Vec<(String, Column)>,
+}
+
+fn shape_column(shape: Shape) -> Column {
+    match shape {
+        Shape::Rock => Column::X,
+        Shape::Paper => Column::Y,
+        Shape::Scissors => Column::Z,
+    }
+}
+
+impl Notation {
+    fn from_shapes(names: [&str; 3]) -> Self {
+        Self {
+            opponent: names
+                .iter()
+                .zip(Shape::ALL)
+                .map(|(n, s)| (n.to_string(), s))
+                .collect(),
+            column: names
+                .iter()
+                .zip(Shape::ALL)
+                .map(|(n, s)| (n.to_string(), shape_column(s)))
+                .collect(),
+        }
+    }
+
+    /// Puzzle encoding: `A X`
+    pub fn letters() -> Self {
+        Self {
+            opponent: vec![
+                ("A".to_owned(), Shape::Rock),
+                ("B".to_owned(), Shape::Paper),
+                ("C".to_owned(), Shape::Scissors),
+            ],
+            column: vec![
+                ("X".to_owned(), Column::X),
+                ("Y".to_owned(), Column::Y),
+                ("Z".to_owned(), Column::Z),
+            ],
+        }
+    }
+
+    /// `Rock Paper`
+    pub fn words() -> Self {
+        Self::from_shapes(["Rock", "Paper", "Scissors"])
+    }
+
+    /// `R P`
+    pub fn shape_letters() -> Self {
+        Self::from_shapes(["R", "P", "S"])
+    }
+
+    /// Accepts any of the built-in notations, even mixed within a single line.
+    pub fn mixed() -> Self {
+        let mut mixed = Self::letters();
+        for other in [Self::words(), Self::shape_letters()] {
+            mixed.opponent.extend(other.opponent);
+            mixed.column.extend(other.column);
+        }
+        mixed
+    }
+
+    /// Parses header declaring the symbols of both columns, e.g.
+    /// `# R=Rock P=Paper S=Scissors | L=X D=Y W=Z`.
+    ///
+    /// Opponent symbols map to `A`/`B`/`C` or a shape name, second column symbols to `X`/`Y`/`Z`
+    /// or a shape name.
+    pub fn from_header(line: &str) -> Result<Self, ParseErrorKind> {
+        let invalid = |msg: String| ParseErrorKind::InvalidHeader(msg);
+        let declaration = line
+            .trim()
+            .strip_prefix('#')
+            .ok_or_else(|| invalid("header has to start with '#'".to_owned()))?;
+        let Some((opponent, column)) = declaration.split_once('|') else {
+            return Err(invalid(
+                "expected two column mappings separated by '|'".to_owned(),
+            ));
+        };
+
+        let known = Self::letters();
+        let words = Self::words();
+        let mut notation = Self {
+            opponent: vec![],
+            column: vec![],
+        };
+        for (symbol, meaning) in mappings(opponent)? {
+            let shape = known
+                .opponent_shape(meaning)
+                .or_else(|| words.opponent_shape(meaning))
+                .ok_or_else(|| invalid(format!("{} is not an opponent shape", meaning)))?;
+            if notation.opponent_shape(symbol).is_some() {
+                return Err(invalid(format!(
+                    "opponent symbol {} declared twice",
+                    symbol
+                )));
+            }
+            notation.opponent.push((symbol.to_owned(), shape));
+        }
+        for (symbol, meaning) in mappings(column)? {
+            let column = known
+                .column_value(meaning)
+                .or_else(|| words.column_value(meaning))
+                .ok_or_else(|| invalid(format!("{} is not a column value", meaning)))?;
+            if notation.column_value(symbol).is_some() {
+                return Err(invalid(format!("column symbol {} declared twice", symbol)));
+            }
+            notation.column.push((symbol.to_owned(), column));
+        }
+        Ok(notation)
+    }
+
+    fn opponent_shape(&self, symbol: &str) -> Option<Shape> {
+        self.opponent
+            .iter()
+            .find(|(s, _)| s == symbol)
+            .map(|(_, shape)| *shape)
+    }
+
+    fn column_value(&self, symbol: &str) -> Option<Column> {
+        self.column
+            .iter()
+            .find(|(s, _)| s == symbol)
+            .map(|(_, column)| *column)
+    }
+
+    pub fn parse_round(&self, line: &str) -> Result<Round, ParseErrorKind> {
+        let mut symbols = line.split_whitespace();
+        let (Some(opponent), Some(column), None) = (symbols.next(), symbols.next(), symbols.next())
+        else {
+            return Err(ParseErrorKind::Malformed(line.to_owned()));
+        };
+        Ok(Round {
+            opponent: self
+                .opponent_shape(opponent)
+                .ok_or_else(|| ParseErrorKind::UnknownOpponent(opponent.to_owned()))?,
+            column: self
+                .column_value(column)
+                .ok_or_else(|| ParseErrorKind::UnknownColumn(column.to_owned()))?,
+        })
+    }
+}
+
+/// Splits `a=b c=d` declarations
+fn mappings(declaration: &str) -> Result<Vec<(&str, &str)>, ParseErrorKind> {
+    declaration
+        .split_whitespace()
+        .map(|m| {
+            m.split_once('=')
+                .filter(|(s, m)| !s.is_empty() && !m.is_empty())
+                .ok_or_else(|| ParseErrorKind::InvalidHeader(format!("invalid mapping {}", m)))
+        })
+        .collect()
+}
+
+/// Parses guide in any notation. If the first non-blank line is a `#` header, its mapping is
+/// used for the whole guide, otherwise `Notation::mixed` is used. Blank lines are skipped.
+pub fn parse_guide(reader: impl BufRead) -> Result<Vec<Round>> {
+    let mut notation = None;
+    let mut rounds = vec![];
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let with_line = |kind| ParseError { line: i + 1, kind };
+
+        if notation.is_none() && line.trim_start().starts_with('#') {
+            notation = Some(Notation::from_header(&line).map_err(with_line)?);
+            continue;
+        }
+        let notation = notation.get_or_insert_with(Notation::mixed);
+        rounds.push(notation.parse_round(&line).map_err(with_line)?);
+    }
+    Ok(rounds)
+}
+
+pub fn load_guide(file: &str) -> Result<Vec<Round>> {
+    let file = File::open(file)?;
+    parse_guide(BufReader::new(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{load_rounds, ColumnAsOutcome, ColumnAsShape};
+
+    #[test]
+    fn all_notations_give_same_rounds() {
+        let expected = load_rounds("test_data.txt").unwrap();
+        let guides = [
+            "A Y\nB X\nC Z",
+            "Rock Paper\nPaper Rock\nScissors Scissors",
+            "R P\nP R\nS S",
+            "A Paper\nP X\nScissors S",
+            "# R=Rock P=Paper S=Scissors | r=X p=Y s=Z\nR p\nP r\nS s",
+            "# 1=A 2=B 3=C | L=Rock D=Paper W=Scissors\n\n1 D\n2 L\n3 W\n",
+        ];
+        for guide in guides {
+            assert_eq!(
+                parse_guide(guide.as_bytes()).unwrap(),
+                expected,
+                "{}",
+                guide
+            );
+        }
+    }
+
+    #[test]
+    fn strategies_score_other_notations() {
+        let rounds = parse_guide("Rock Paper\nPaper Rock\nScissors Scissors".as_bytes()).unwrap();
+        let score = |s: u32, r: &Round| s + r.calc_score(&ColumnAsShape);
+        assert_eq!(rounds.iter().fold(0, score), 15);
+        let score = |s: u32, r: &Round| s + r.calc_score(&ColumnAsOutcome);
+        assert_eq!(rounds.iter().fold(0, score), 12);
+    }
+
+    #[test]
+    fn header_restricts_symbols() {
+        let err = parse_guide("# R=Rock | r=X\nR r\nA X".as_bytes()).unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError {
+                line: 3,
+                kind: ParseErrorKind::UnknownOpponent("A".to_owned())
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_headers() {
+        for header in [
+            "# R=Rock",
+            "# R=Stone | X=X",
+            "# R=Rock | X=Q",
+            "# R=Rock R=Paper | X=X",
+            "# R | X=X",
+        ] {
+            let err = parse_guide(header.as_bytes()).unwrap_err();
+            let err = err.downcast::<ParseError>().unwrap();
+            assert_eq!(err.line, 1);
+            assert!(
+                matches!(err.kind, ParseErrorKind::InvalidHeader(_)),
+                "{}",
+                header
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_words() {
+        let err = parse_guide("Rock Lizard".as_bytes()).unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap().kind,
+            ParseErrorKind::UnknownColumn("Lizard".to_owned())
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{Round, Shape, Strategy};
+
+/// Plays the same shape regardless of the guide.
+pub struct Play(pub Shape);
+
+impl Strategy for Play {
+    fn respond(&self, _round: &Round) -> Shape {
+        self.0
+    }
+}
+
+/// Responses for every round with the total score they give.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub score: u32,
+    pub responses: Vec<Shape>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Optimisation {
+    /// Highest achievable score
+    pub best: Plan,
+    /// Lowest achievable score
+    pub worst: Plan,
+    /// Score of following the encrypted guide
+    pub guide: Plan,
+}
+
+impl Optimisation {
+    /// How many points following the guide loses compared to the optimum
+    pub fn delta(&self) -> u32 {
+        self.best.score - self.guide.score
+    }
+}
+
+impl FromIterator<(Shape, u32)> for Plan {
+    fn from_iter<T: IntoIterator<Item = (Shape, u32)>>(iter: T) -> Self {
+        let mut plan = Plan {
+            score: 0,
+            responses: vec![],
+        };
+        iter.into_iter().for_each(|(shape, score)| {
+            plan.score += score;
+            plan.responses.push(shape);
+        });
+        plan
+    }
+}
+
+/// Finds the best and the worst responses to the opponent's column and compares them with the
+/// guide interpreted by `strategy`. Rounds are independent so the choice is made round by round,
+/// ties going to the shape listed first in `Shape::ALL`.
+pub fn optimise(rounds: &[Round], strategy: &impl Strategy) -> Optimisation {
+    let scored = |r: &Round| Shape::ALL.map(|s| (s, r.calc_score(&Play(s))));
+
+    let best = rounds
+        .iter()
+        .map(|r| {
+            scored(r)
+                .into_iter()
+                .reduce(|a, b| if b.1 > a.1 { b } else { a })
+                .expect("There is at least one shape")
+        })
+        .collect();
+    let worst = rounds
+        .iter()
+        .map(|r| {
+            scored(r)
+                .into_iter()
+                .reduce(|a, b| if b.1 < a.1 { b } else { a })
+                .expect("There is at least one shape")
+        })
+        .collect();
+    let guide = rounds
+        .iter()
+        .map(|r| (strategy.respond(r), r.calc_score(strategy)))
+        .collect();
+
+    Optimisation { best, worst, guide }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{load_rounds, ColumnAsOutcome, ColumnAsShape};
+
+    #[test]
+    fn example() {
+        let rounds = load_rounds("test_data.txt").unwrap();
+        let optimisation = optimise(&rounds, &ColumnAsShape);
+
+        // Winning gives at least 7 points and losing at most 3, so the best plan always wins
+        assert_eq!(
+            optimisation.best.responses,
+            vec![Shape::Paper, Shape::Scissors, Shape::Rock]
+        );
+        assert_eq!(optimisation.best.score, 8 + 9 + 7);
+        assert_eq!(
+            optimisation.worst.responses,
+            vec![Shape::Scissors, Shape::Rock, Shape::Paper]
+        );
+        assert_eq!(optimisation.worst.score, 3 + 1 + 2);
+        assert_eq!(optimisation.guide.score, 15);
+        assert_eq!(optimisation.delta(), 9);
+    }
+
+    #[test]
+    fn input() {
+        let rounds = load_rounds("input.txt").unwrap();
+        let optimisation = optimise(&rounds, &ColumnAsOutcome);
+        assert_eq!(optimisation.guide.score, 14652);
+        assert_eq!(optimisation.best.responses.len(), rounds.len());
+        assert!(optimisation.worst.score <= optimisation.guide.score);
+        assert!(optimisation.guide.score <= optimisation.best.score);
+    }
+}
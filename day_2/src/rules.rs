@@ -0,0 +1,440 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use anyhow::Result;
+
+use crate::{Outcome, ParseError, ParseErrorKind, Round, Shape, Strategy};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapeRule {
+    pub name: String,
+    pub bonus: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutcomeScores {
+    pub lose: u32,
+    pub draw: u32,
+    pub win: u32,
+}
+
+/// Rule table of a cyclic game. Shapes are identified by their index in the table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    shapes: Vec<ShapeRule>,
+    /// `beats[a][b]` is true when shape `a` defeats shape `b`
+    beats: Vec<Vec<bool>>,
+    scores: OutcomeScores,
+}
+
+/// Round of a guide played with `Rules`, both shapes given by their ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RulesRound {
+    pub opponent: usize,
+    pub response: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RulesError {
+    NoShapes,
+    DuplicateShape(String),
+    UnknownShape(String),
+    BeatsItself(String),
+    /// Both shapes are declared to beat each other
+    MutualWin(String, String),
+    /// Neither of the shapes beats the other
+    Undecided(String, String),
+    /// Shape doesn't beat exactly half of the other shapes, so the game isn't cyclic
+    Unbalanced(String),
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoShapes => write!(f, "rules need at least one shape"),
+            Self::DuplicateShape(s) => write!(f, "shape {} is declared twice", s),
+            Self::UnknownShape(s) => write!(f, "unknown shape {}", s),
+            Self::BeatsItself(s) => write!(f, "shape {} beats itself", s),
+            Self::MutualWin(a, b) => write!(f, "{} and {} beat each other", a, b),
+            Self::Undecided(a, b) => write!(f, "neither {} nor {} wins", a, b),
+            Self::Unbalanced(s) => write!(f, "{} doesn't beat half of the other shapes", s),
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+impl Rules {
+    /// Creates rules from shapes and `(winner, loser)` pairs of shape names.
+    ///
+    /// The relation has to be a tournament: every two different shapes are decided by exactly one pair.
+    /// It also has to be balanced, every shape beating half of the others, which is only possible
+    /// for an odd number of shapes.
+    pub fn new(
+        shapes: Vec<ShapeRule>,
+        wins: &[(&str, &str)],
+        scores: OutcomeScores,
+    ) -> Result<Self, RulesError> {
+        if shapes.is_empty() {
+            return Err(RulesError::NoShapes);
+        }
+        for (i, shape) in shapes.iter().enumerate() {
+            if shapes[..i].iter().any(|s| s.name == shape.name) {
+                return Err(RulesError::DuplicateShape(shape.name.clone()));
+            }
+        }
+
+        let mut rules = Self {
+            beats: vec![vec![false; shapes.len()]; shapes.len()],
+            shapes,
+            scores,
+        };
+        for (winner, loser) in wins {
+            let w = rules.find(winner)?;
+            let l = rules.find(loser)?;
+            if w == l {
+                return Err(RulesError::BeatsItself(winner.to_string()));
+            }
+            rules.beats[w][l] = true;
+        }
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    /// Rules of the Advent of Code puzzle. Shapes are listed in the order of `Shape::ALL`.
+    pub fn classic() -> Self {
+        let shapes = ["Rock", "Paper", "Scissors"]
+            .iter()
+            .zip(1..)
+            .map(|(name, bonus)| ShapeRule {
+                name: name.to_string(),
+                bonus,
+            })
+            .collect();
+        let wins = [
+            ("Rock", "Scissors"),
+            ("Paper", "Rock"),
+            ("Scissors", "Paper"),
+        ];
+        Self::new(shapes, &wins, OutcomeScores::default()).expect("Classic rules are valid")
+    }
+
+    /// Rock-Paper-Scissors-Lizard-Spock
+    pub fn rpsls() -> Self {
+        let shapes = ["Rock", "Paper", "Scissors", "Lizard", "Spock"]
+            .iter()
+            .zip(1..)
+            .map(|(name, bonus)| ShapeRule {
+                name: name.to_string(),
+                bonus,
+            })
+            .collect();
+        let wins = [
+            ("Scissors", "Paper"),
+            ("Paper", "Rock"),
+            ("Rock", "Lizard"),
+            ("Lizard", "Spock"),
+            ("Spock", "Scissors"),
+            ("Scissors", "Lizard"),
+            ("Lizard", "Paper"),
+            ("Paper", "Spock"),
+            ("Spock", "Rock"),
+            ("Rock", "Scissors"),
+        ];
+        Self::new(shapes, &wins, OutcomeScores::default()).expect("RPSLS rules are valid")
+    }
+
+    fn find(&self, name: &str) -> Result<usize, RulesError> {
+        self.shape_id(name)
+            .ok_or_else(|| RulesError::UnknownShape(name.to_owned()))
+    }
+
+    fn validate(&self) -> Result<(), RulesError> {
+        for a in 0..self.shapes.len() {
+            for b in a + 1..self.shapes.len() {
+                let (a_name, b_name) = (self.name(a).to_owned(), self.name(b).to_owned());
+                match (self.beats[a][b], self.beats[b][a]) {
+                    (true, true) => return Err(RulesError::MutualWin(a_name, b_name)),
+                    (false, false) => return Err(RulesError::Undecided(a_name, b_name)),
+                    _ => (),
+                }
+            }
+        }
+        let n = self.shapes.len();
+        match (0..n).find(|s| self.beats[*s].iter().filter(|b| **b).count() * 2 != n - 1) {
+            Some(s) => Err(RulesError::Unbalanced(self.name(s).to_owned())),
+            None => Ok(()),
+        }
+    }
+
+    pub fn shape_count(&self) -> usize {
+        self.shapes.len()
+    }
+
+    pub fn shape_id(&self, name: &str) -> Option<usize> {
+        self.shapes.iter().position(|s| s.name == name)
+    }
+
+    pub fn name(&self, shape: usize) -> &str {
+        &self.shapes[shape].name
+    }
+
+    pub fn bonus(&self, shape: usize) -> u32 {
+        self.shapes[shape].bonus
+    }
+
+    pub fn beats(&self, winner: usize, loser: usize) -> bool {
+        self.beats[winner][loser]
+    }
+
+    /// Outcome of playing `shape` against `opponent`
+    pub fn outcome(&self, shape: usize, opponent: usize) -> Outcome {
+        if shape == opponent {
+            Outcome::Draw
+        } else if self.beats[shape][opponent] {
+            Outcome::Win
+        } else {
+            Outcome::Lose
+        }
+    }
+
+    pub fn outcome_score(&self, outcome: Outcome) -> u32 {
+        match outcome {
+            Outcome::Lose => self.scores.lose,
+            Outcome::Draw => self.scores.draw,
+            Outcome::Win => self.scores.win,
+        }
+    }
+
+    /// Score of playing `shape` against `opponent`. Shapes come in the same order as in
+    /// `Rules::outcome`.
+    pub fn score(&self, shape: usize, opponent: usize) -> u32 {
+        self.bonus(shape) + self.outcome_score(self.outcome(shape, opponent))
+    }
+
+    /// Shapes giving `outcome` against `opponent`
+    pub fn responses(&self, opponent: usize, outcome: Outcome) -> impl Iterator<Item = usize> + '_ {
+        (0..self.shapes.len()).filter(move |s| self.outcome(*s, opponent) == outcome)
+    }
+
+    /// Id of the shape named `Rock`, `Paper` or `Scissors`, if the table has one
+    pub fn shape_of(&self, shape: Shape) -> Option<usize> {
+        let name = match shape {
+            Shape::Rock => "Rock",
+            Shape::Paper => "Paper",
+            Shape::Scissors => "Scissors",
+        };
+        self.shape_id(name)
+    }
+
+    /// Translates a classic round read by `strategy`. Returns `None` if the table lacks one of the
+    /// shapes.
+    pub fn round(&self, round: &Round, strategy: &impl Strategy) -> Option<RulesRound> {
+        Some(RulesRound {
+            opponent: self.shape_of(round.opponent)?,
+            response: self.shape_of(strategy.respond(round))?,
+        })
+    }
+
+    /// Parses `Spock Lizard` style round naming the opponent's shape and ours.
+    pub fn parse_round(&self, line: &str) -> Result<RulesRound, ParseErrorKind> {
+        let mut symbols = line.split_whitespace();
+        let (Some(opponent), Some(response), None) =
+            (symbols.next(), symbols.next(), symbols.next())
+        else {
+            return Err(ParseErrorKind::Malformed(line.to_owned()));
+        };
+        Ok(RulesRound {
+            opponent: self
+                .shape_id(opponent)
+                .ok_or_else(|| ParseErrorKind::UnknownOpponent(opponent.to_owned()))?,
+            response: self
+                .shape_id(response)
+                .ok_or_else(|| ParseErrorKind::UnknownColumn(response.to_owned()))?,
+        })
+    }
+
+    /// Parses guide naming shapes of the table, skipping blank lines.
+    pub fn parse_guide(&self, reader: impl BufRead) -> Result<Vec<RulesRound>> {
+        let mut rounds = vec![];
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let round = self
+                .parse_round(&line)
+                .map_err(|kind| ParseError { line: i + 1, kind })?;
+            rounds.push(round);
+        }
+        Ok(rounds)
+    }
+
+    pub fn load_guide(&self, file: &str) -> Result<Vec<RulesRound>> {
+        let file = File::open(file)?;
+        self.parse_guide(BufReader::new(file))
+    }
+
+    pub fn score_round(&self, round: &RulesRound) -> u32 {
+        self.score(round.response, round.opponent)
+    }
+}
+
+impl Default for OutcomeScores {
+    fn default() -> Self {
+        Self {
+            lose: 0,
+            draw: 3,
+            win: 6,
+        }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{load_rounds, ColumnAsOutcome, ColumnAsShape};
+
+    fn shapes(names: &[&str]) -> Vec<ShapeRule> {
+        names
+            .iter()
+            .map(|n| ShapeRule {
+                name: n.to_string(),
+                bonus: 0,
+            })
+            .collect()
+    }
+
+    fn guide_score(rules: &Rules, rounds: &[Round], strategy: &impl Strategy) -> u32 {
+        rounds
+            .iter()
+            .map(|r| rules.score_round(&rules.round(r, strategy).unwrap()))
+            .sum()
+    }
+
+    #[test]
+    fn classic_rules_match_shapes() {
+        let rules = Rules::default();
+        for shape in Shape::ALL {
+            assert_eq!(rules.shape_of(shape), Some(shape.id()));
+        }
+        // Score of every shape, against Rock, Paper and Scissors
+        let expected = [[4, 1, 7], [8, 5, 2], [3, 9, 6]];
+        for (shape, scores) in expected.iter().enumerate() {
+            for (opponent, score) in scores.iter().enumerate() {
+                assert_eq!(rules.score(shape, opponent), *score);
+            }
+        }
+
+        let rounds = load_rounds("input.txt").unwrap();
+        assert_eq!(guide_score(&rules, &rounds, &ColumnAsShape), 10718);
+        assert_eq!(guide_score(&rules, &rounds, &ColumnAsOutcome), 14652);
+    }
+
+    #[test]
+    fn rpsls() {
+        let rules = Rules::rpsls();
+        assert_eq!(rules.shape_count(), 5);
+        let spock = rules.shape_id("Spock").unwrap();
+        let lizard = rules.shape_id("Lizard").unwrap();
+        assert_eq!(rules.outcome(lizard, spock), Outcome::Win);
+        assert_eq!(rules.score(lizard, spock), 4 + 6);
+        assert_eq!(rules.score(spock, lizard), 5);
+        let winners: Vec<_> = rules.responses(spock, Outcome::Win).collect();
+        assert_eq!(winners, vec![rules.shape_of(Shape::Paper).unwrap(), lizard]);
+
+        // Classic rounds keep their scores as Rock, Paper and Scissors interact the same way
+        let rounds = load_rounds("test_data.txt").unwrap();
+        assert_eq!(guide_score(&rules, &rounds, &ColumnAsShape), 15);
+    }
+
+    #[test]
+    fn rpsls_guide() {
+        let rules = Rules::rpsls();
+        let guide = "Spock Lizard\n\nRock Spock\n  Lizard   Lizard \nScissors Rock";
+        let rounds = rules.parse_guide(guide.as_bytes()).unwrap();
+        assert_eq!(
+            rounds[0],
+            RulesRound {
+                opponent: rules.shape_id("Spock").unwrap(),
+                response: rules.shape_id("Lizard").unwrap(),
+            }
+        );
+        let scores: Vec<_> = rounds.iter().map(|r| rules.score_round(r)).collect();
+        assert_eq!(scores, vec![4 + 6, 5 + 6, 4 + 3, 1 + 6]);
+
+        // Classic rules don't know the extra shapes
+        let err = Rules::classic().parse_guide(guide.as_bytes()).unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError {
+                line: 1,
+                kind: ParseErrorKind::UnknownOpponent("Spock".to_owned())
+            }
+        );
+        let err = rules.parse_guide("Rock\nRock Rock".as_bytes()).unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap().kind,
+            ParseErrorKind::Malformed("Rock".to_owned())
+        );
+    }
+
+    #[test]
+    fn invalid_rules() {
+        let scores = OutcomeScores::default();
+        assert_eq!(Rules::new(vec![], &[], scores), Err(RulesError::NoShapes));
+        assert_eq!(
+            Rules::new(shapes(&["a", "a"]), &[], scores),
+            Err(RulesError::DuplicateShape("a".to_owned()))
+        );
+        assert_eq!(
+            Rules::new(shapes(&["a", "b"]), &[("a", "c")], scores),
+            Err(RulesError::UnknownShape("c".to_owned()))
+        );
+        assert_eq!(
+            Rules::new(shapes(&["a", "b"]), &[("a", "a")], scores),
+            Err(RulesError::BeatsItself("a".to_owned()))
+        );
+        assert_eq!(
+            Rules::new(shapes(&["a", "b"]), &[("a", "b"), ("b", "a")], scores),
+            Err(RulesError::MutualWin("a".to_owned(), "b".to_owned()))
+        );
+        assert_eq!(
+            Rules::new(shapes(&["a", "b", "c"]), &[("a", "b"), ("b", "c")], scores),
+            Err(RulesError::Undecided("a".to_owned(), "c".to_owned()))
+        );
+    }
+
+    #[test]
+    fn unbalanced_tournament() {
+        let scores = OutcomeScores::default();
+        let wins = [("a", "b"), ("a", "c"), ("b", "c")];
+        assert_eq!(
+            Rules::new(shapes(&["a", "b", "c"]), &wins, scores),
+            Err(RulesError::Unbalanced("a".to_owned()))
+        );
+        // Even number of shapes can't be balanced
+        let wins = [
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "d"),
+            ("d", "a"),
+            ("a", "c"),
+            ("b", "d"),
+        ];
+        assert_eq!(
+            Rules::new(shapes(&["a", "b", "c", "d"]), &wins, scores),
+            Err(RulesError::Unbalanced("a".to_owned()))
+        );
+        assert!(Rules::new(shapes(&["a"]), &[], scores).is_ok());
+    }
+}
@@ -0,0 +1,317 @@
+use std::cmp::Reverse;
+
+use crate::{Outcome, Round, Shape, Strategy};
+
+/// Small seeded generator (SplitMix64) so that tournaments are reproducible.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn shape(&mut self) -> Shape {
+        Shape::ALL[(self.next_u64() % Shape::ALL.len() as u64) as usize]
+    }
+}
+
+/// Participant of a tournament. A player only sees the shapes the opponent has played so far.
+pub trait Player {
+    fn name(&self) -> String;
+    fn play(&mut self, rng: &mut Rng) -> Shape;
+    fn observe(&mut self, opponent: Shape);
+    /// Forgets everything learned about the previous opponent
+    fn reset(&mut self);
+}
+
+/// Replays the shapes from a strategy guide, starting over when they run out.
+pub struct FixedGuide {
+    name: String,
+    shapes: Vec<Shape>,
+    next: usize,
+}
+
+/// Plays uniformly random shapes.
+pub struct RandomPlayer;
+
+/// Beats the shape the opponent has played most often.
+#[derive(Default)]
+pub struct FrequencyPredictor {
+    counts: [u32; 3],
+}
+
+/// Beats the shape the opponent most often played after their last shape.
+#[derive(Default)]
+pub struct MarkovPredictor {
+    last: Option<Shape>,
+    transitions: [[u32; 3]; 3],
+}
+
+impl FixedGuide {
+    /// Returns `None` for a guide without any shapes to play
+    pub fn new(name: &str, shapes: Vec<Shape>) -> Option<Self> {
+        (!shapes.is_empty()).then_some(Self {
+            name: name.to_owned(),
+            shapes,
+            next: 0,
+        })
+    }
+
+    /// Plays the responses `strategy` reads from the guide. Returns `None` for a guide without
+    /// any rounds.
+    pub fn from_rounds(name: &str, rounds: &[Round], strategy: &impl Strategy) -> Option<Self> {
+        Self::new(name, rounds.iter().map(|r| strategy.respond(r)).collect())
+    }
+}
+
+/// Picks the shape beating the most likely one or a random one if nothing is known yet.
+fn counter_prediction(counts: &[u32; 3], rng: &mut Rng) -> Shape {
+    let (index, max) = counts
+        .iter()
+        .enumerate()
+        .max_by_key(|(i, c)| (**c, Reverse(*i)))
+        .expect("There are three shapes");
+    if *max == 0 {
+        rng.shape()
+    } else {
+        Shape::ALL[index].beaten_by()
+    }
+}
+
+impl Player for FixedGuide {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn play(&mut self, _rng: &mut Rng) -> Shape {
+        let shape = self.shapes[self.next];
+        self.next = (self.next + 1) % self.shapes.len();
+        shape
+    }
+
+    fn observe(&mut self, _opponent: Shape) {}
+
+    fn reset(&mut self) {
+        self.next = 0;
+    }
+}
+
+impl Player for RandomPlayer {
+    fn name(&self) -> String {
+        "random".to_owned()
+    }
+
+    fn play(&mut self, rng: &mut Rng) -> Shape {
+        rng.shape()
+    }
+
+    fn observe(&mut self, _opponent: Shape) {}
+
+    fn reset(&mut self) {}
+}
+
+impl Player for FrequencyPredictor {
+    fn name(&self) -> String {
+        "frequency".to_owned()
+    }
+
+    fn play(&mut self, rng: &mut Rng) -> Shape {
+        counter_prediction(&self.counts, rng)
+    }
+
+    fn observe(&mut self, opponent: Shape) {
+        self.counts[opponent.id()] += 1;
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl Player for MarkovPredictor {
+    fn name(&self) -> String {
+        "markov".to_owned()
+    }
+
+    fn play(&mut self, rng: &mut Rng) -> Shape {
+        match self.last {
+            Some(last) => counter_prediction(&self.transitions[last.id()], rng),
+            None => rng.shape(),
+        }
+    }
+
+    fn observe(&mut self, opponent: Shape) {
+        if let Some(last) = self.last {
+            self.transitions[last.id()][opponent.id()] += 1;
+        }
+        self.last = Some(opponent);
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Standing {
+    pub name: String,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    /// Sum of the round scores from all matches
+    pub score: u32,
+}
+
+impl Standing {
+    /// Three points for a won match and one for a draw
+    pub fn points(&self) -> usize {
+        self.wins * 3 + self.draws
+    }
+}
+
+pub struct Tournament {
+    pub rounds_per_match: usize,
+    pub seed: u64,
+}
+
+impl Tournament {
+    /// Plays a single match and returns the scores of both players.
+    pub fn play_match(&self, a: &mut dyn Player, b: &mut dyn Player, rng: &mut Rng) -> (u32, u32) {
+        a.reset();
+        b.reset();
+        let mut scores = (0, 0);
+        for _ in 0..self.rounds_per_match {
+            let shape_a = a.play(rng);
+            let shape_b = b.play(rng);
+            scores.0 += shape_a.score_against(shape_b);
+            scores.1 += shape_b.score_against(shape_a);
+            a.observe(shape_b);
+            b.observe(shape_a);
+        }
+        scores
+    }
+
+    /// Plays every pair of players once and returns standings from the best player.
+    pub fn run(&self, players: &mut [Box<dyn Player>]) -> Vec<Standing> {
+        let mut rng = Rng::new(self.seed);
+        let mut standings: Vec<Standing> = players
+            .iter()
+            .map(|p| Standing {
+                name: p.name(),
+                ..Default::default()
+            })
+            .collect();
+
+        for i in 0..players.len() {
+            for j in i + 1..players.len() {
+                let (left, right) = players.split_at_mut(j);
+                let (score_i, score_j) =
+                    self.play_match(left[i].as_mut(), right[0].as_mut(), &mut rng);
+
+                standings[i].score += score_i;
+                standings[j].score += score_j;
+                let outcome = match score_i.cmp(&score_j) {
+                    std::cmp::Ordering::Less => Outcome::Lose,
+                    std::cmp::Ordering::Equal => Outcome::Draw,
+                    std::cmp::Ordering::Greater => Outcome::Win,
+                };
+                match outcome {
+                    Outcome::Win => {
+                        standings[i].wins += 1;
+                        standings[j].losses += 1;
+                    }
+                    Outcome::Draw => {
+                        standings[i].draws += 1;
+                        standings[j].draws += 1;
+                    }
+                    Outcome::Lose => {
+                        standings[i].losses += 1;
+                        standings[j].wins += 1;
+                    }
+                }
+            }
+        }
+
+        standings.sort_by_key(|s| Reverse((s.points(), s.score)));
+        standings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{load_rounds, parse_rounds, ColumnAsOutcome, ColumnAsShape};
+
+    fn players() -> Vec<Box<dyn Player>> {
+        let rounds = load_rounds("input.txt").unwrap();
+        vec![
+            Box::new(FixedGuide::new("rock", vec![Shape::Rock]).unwrap()),
+            Box::new(FixedGuide::from_rounds("guide", &rounds, &ColumnAsOutcome).unwrap()),
+            Box::new(RandomPlayer),
+            Box::new(FrequencyPredictor::default()),
+            Box::new(MarkovPredictor::default()),
+        ]
+    }
+
+    #[test]
+    fn seeded_runs_are_reproducible() {
+        let tournament = Tournament {
+            rounds_per_match: 200,
+            seed: 2022,
+        };
+        let first = tournament.run(&mut players());
+        let second = tournament.run(&mut players());
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 5);
+        for standing in &first {
+            assert_eq!(standing.wins + standing.draws + standing.losses, 4);
+        }
+    }
+
+    #[test]
+    fn predictors_beat_constant_player() {
+        let tournament = Tournament {
+            rounds_per_match: 100,
+            seed: 7,
+        };
+        let mut rng = Rng::new(7);
+        let mut rock = FixedGuide::new("rock", vec![Shape::Rock]).unwrap();
+
+        let (rock_score, frequency_score) =
+            tournament.play_match(&mut rock, &mut FrequencyPredictor::default(), &mut rng);
+        assert!(frequency_score > rock_score);
+
+        let (rock_score, markov_score) =
+            tournament.play_match(&mut rock, &mut MarkovPredictor::default(), &mut rng);
+        assert!(markov_score > rock_score);
+    }
+
+    #[test]
+    fn markov_learns_cycles() {
+        let mut cycle = FixedGuide::new("cycle", Shape::ALL.to_vec()).unwrap();
+        let mut markov = MarkovPredictor::default();
+        let mut rng = Rng::new(1);
+        for _ in 0..30 {
+            let shape = cycle.play(&mut rng);
+            markov.observe(shape);
+        }
+        // Cycle continues with Rock which is beaten by Paper
+        assert_eq!(markov.play(&mut rng), Shape::Paper);
+    }
+
+    #[test]
+    fn empty_guide() {
+        assert!(FixedGuide::new("empty", vec![]).is_none());
+        let rounds = parse_rounds("\n  \n".as_bytes()).unwrap();
+        assert!(FixedGuide::from_rounds("blank", &rounds, &ColumnAsShape).is_none());
+    }
+}
@@ -0,0 +1,118 @@
+use std::cmp::Reverse;
+
+use crate::item_set::ItemSet;
+use crate::{RucksackError, Rucksacks};
+
+impl Rucksacks {
+    /// Counts how many sets contain each item. Most frequent items come first, ties keep the
+    /// table order. Items which never appear are left out.
+    fn frequencies(&self, sets: impl IntoIterator<Item = ItemSet>) -> Vec<(char, usize)> {
+        let mut counts = vec![0; self.table.len()];
+        sets.into_iter()
+            .for_each(|set| set.iter().for_each(|i| counts[i] += 1));
+
+        let mut frequencies: Vec<_> = counts
+            .into_iter()
+            .enumerate()
+            .filter(|(_, count)| *count > 0)
+            .map(|(i, count)| (self.table.item(i), count))
+            .collect();
+        frequencies.sort_by_key(|(_, count)| Reverse(*count));
+        frequencies
+    }
+
+    /// All items present in both compartments, for every rucksack
+    pub fn shared_items(&self) -> Vec<Vec<char>> {
+        self.rucksacks
+            .iter()
+            .map(|r| self.table.items(r.duplications()).collect())
+            .collect()
+    }
+
+    /// How many rucksacks have each item in both compartments
+    pub fn misplaced_frequencies(&self) -> Vec<(char, usize)> {
+        self.frequencies(self.rucksacks.iter().map(|r| r.duplications()))
+    }
+
+    /// How many groups of `group_size` use each item as a badge
+    pub fn badge_frequencies(
+        &self,
+        group_size: usize,
+    ) -> Result<Vec<(char, usize)>, RucksackError> {
+        let badges = self.badges(group_size)?;
+        let badges = badges.iter().map(|b| self.table.set([b]));
+        Ok(self.frequencies(badges))
+    }
+
+    /// Items found in every rucksack
+    pub fn common_items(&self) -> Vec<char> {
+        let common = self
+            .rucksacks
+            .iter()
+            .map(|r| r.item_set())
+            .reduce(|a, b| a & b)
+            .unwrap_or_default();
+        self.table.items(common).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Rucksacks;
+
+    #[test]
+    fn example() {
+        let rs = Rucksacks::new("example.txt").unwrap();
+        assert_eq!(
+            rs.shared_items(),
+            vec![
+                vec!['p'],
+                vec!['L'],
+                vec!['P'],
+                vec!['v'],
+                vec!['t'],
+                vec!['s']
+            ]
+        );
+        assert_eq!(rs.misplaced_frequencies().len(), 6);
+        assert_eq!(rs.badge_frequencies(3).unwrap(), vec![('r', 1), ('Z', 1)]);
+        assert_eq!(rs.common_items(), Vec::<char>::new());
+    }
+
+    #[test]
+    fn multiple_shared_items() {
+        let rs = Rucksacks::parse("abcXcbaX\nxyzXXzyx\nabXXbaXX\nXaXbXaXc".as_bytes()).unwrap();
+        assert_eq!(
+            rs.shared_items(),
+            vec![
+                vec!['a', 'b', 'c', 'X'],
+                vec!['x', 'y', 'z', 'X'],
+                vec!['a', 'b', 'X'],
+                vec!['a', 'X']
+            ]
+        );
+        assert_eq!(
+            rs.misplaced_frequencies(),
+            vec![
+                ('X', 4),
+                ('a', 3),
+                ('b', 2),
+                ('c', 1),
+                ('x', 1),
+                ('y', 1),
+                ('z', 1)
+            ]
+        );
+        assert_eq!(rs.common_items(), vec!['X']);
+        // Badge is the first common item in the table order
+        assert_eq!(rs.badge_frequencies(2).unwrap(), vec![('a', 1), ('X', 1)]);
+    }
+
+    #[test]
+    fn input_badges() {
+        let rs = Rucksacks::new("first.txt").unwrap();
+        let badges = rs.badge_frequencies(3).unwrap();
+        assert_eq!(badges.iter().map(|(_, c)| c).sum::<usize>(), 100);
+        assert!(badges.windows(2).all(|w| w[0].1 >= w[1].1));
+    }
+}
@@ -0,0 +1,219 @@
+use crate::item_set::ItemSet;
+use crate::{RucksackError, Rucksacks};
+
+/// Exact cover search (Knuth's Algorithm X) over the candidate groups: every rucksack has to be
+/// covered by exactly one group. The rucksack with the fewest remaining candidates is always
+/// covered first, which keeps backtracking low.
+struct Search {
+    candidates: Vec<Vec<usize>>,
+    /// Candidates containing each rucksack
+    containing: Vec<Vec<usize>>,
+    alive: Vec<bool>,
+    /// Number of alive candidates for each rucksack
+    counts: Vec<usize>,
+    covered: Vec<bool>,
+    solution: Vec<usize>,
+}
+
+impl Search {
+    fn new(rucksacks: usize, candidates: Vec<Vec<usize>>) -> Self {
+        let mut containing = vec![vec![]; rucksacks];
+        for (c, group) in candidates.iter().enumerate() {
+            group.iter().for_each(|r| containing[*r].push(c));
+        }
+        Self {
+            alive: vec![true; candidates.len()],
+            counts: containing.iter().map(|c| c.len()).collect(),
+            covered: vec![false; rucksacks],
+            solution: vec![],
+            candidates,
+            containing,
+        }
+    }
+
+    /// Covers members of the candidate and removes all candidates clashing with it.
+    /// Returns removed candidates so that `uncover` can restore them.
+    fn cover(&mut self, candidate: usize) -> Vec<usize> {
+        let mut removed = vec![];
+        for &member in &self.candidates[candidate] {
+            self.covered[member] = true;
+            for &other in &self.containing[member] {
+                if self.alive[other] {
+                    self.alive[other] = false;
+                    removed.push(other);
+                    self.candidates[other]
+                        .iter()
+                        .for_each(|m| self.counts[*m] -= 1);
+                }
+            }
+        }
+        removed
+    }
+
+    fn uncover(&mut self, candidate: usize, removed: Vec<usize>) {
+        for other in removed {
+            self.alive[other] = true;
+            self.candidates[other]
+                .iter()
+                .for_each(|m| self.counts[*m] += 1);
+        }
+        for &member in &self.candidates[candidate] {
+            self.covered[member] = false;
+        }
+    }
+
+    fn solve(&mut self) -> bool {
+        let Some(rucksack) = (0..self.covered.len())
+            .filter(|r| !self.covered[*r])
+            .min_by_key(|r| self.counts[*r])
+        else {
+            return true;
+        };
+
+        let options: Vec<usize> = self.containing[rucksack]
+            .iter()
+            .copied()
+            .filter(|c| self.alive[*c])
+            .collect();
+        for candidate in options {
+            let removed = self.cover(candidate);
+            self.solution.push(candidate);
+            if self.solve() {
+                return true;
+            }
+            self.solution.pop();
+            self.uncover(candidate, removed);
+        }
+        false
+    }
+}
+
+/// Lists every group of `group_size` rucksacks with exactly one item in common.
+fn candidates(sets: &[ItemSet], group_size: usize) -> Vec<Vec<usize>> {
+    fn extend(
+        sets: &[ItemSet],
+        group_size: usize,
+        group: &mut Vec<usize>,
+        common: ItemSet,
+        found: &mut Vec<Vec<usize>>,
+    ) {
+        if group.len() == group_size {
+            if common.len() == 1 {
+                found.push(group.clone());
+            }
+            return;
+        }
+        let start = group.last().map_or(0, |l| l + 1);
+        for next in start..sets.len() {
+            let shared = common & sets[next];
+            if shared.is_empty() {
+                continue;
+            }
+            group.push(next);
+            extend(sets, group_size, group, shared, found);
+            group.pop();
+        }
+    }
+
+    let mut found = vec![];
+    for first in 0..sets.len() {
+        extend(sets, group_size, &mut vec![first], sets[first], &mut found);
+    }
+    found
+}
+
+impl Rucksacks {
+    /// Finds groups of `group_size` rucksacks, in any order, such that each group has exactly one
+    /// item in common. Groups hold indices of rucksacks. Returns `None` if the search proves that
+    /// no such partition exists.
+    pub fn infer_groups(
+        &self,
+        group_size: usize,
+    ) -> Result<Option<Vec<Vec<usize>>>, RucksackError> {
+        // Only checks that the rucksacks can be split evenly
+        self.groups(group_size)?;
+
+        let sets: Vec<_> = self.rucksacks.iter().map(|r| r.item_set()).collect();
+        let mut search = Search::new(sets.len(), candidates(&sets, group_size));
+        if !search.solve() {
+            return Ok(None);
+        }
+        Ok(Some(
+            search
+                .solution
+                .iter()
+                .map(|c| search.candidates[*c].clone())
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_valid(rs: &Rucksacks, groups: &[Vec<usize>]) {
+        let mut seen = vec![false; rs.rucksacks.len()];
+        for group in groups {
+            let common = group
+                .iter()
+                .map(|i| rs.rucksacks[*i].item_set())
+                .reduce(|a, b| a & b)
+                .unwrap();
+            assert_eq!(common.len(), 1, "{:?}", group);
+            group.iter().for_each(|i| {
+                assert!(!seen[*i]);
+                seen[*i] = true;
+            });
+        }
+        assert!(seen.into_iter().all(|s| s));
+    }
+
+    fn shuffled(file: &str) -> Rucksacks {
+        let lines: Vec<_> = std::fs::read_to_string(file)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect();
+        // Multiplying by a number coprime with the length permutes the indices
+        let step = (2..).find(|s| gcd(*s, lines.len()) == 1 && *s > 7).unwrap();
+        let shuffled: Vec<_> = (0..lines.len())
+            .map(|i| lines[i * step % lines.len()].as_str())
+            .collect();
+        Rucksacks::parse(shuffled.join("\n").as_bytes()).unwrap()
+    }
+
+    fn gcd(a: usize, b: usize) -> usize {
+        if b == 0 {
+            a
+        } else {
+            gcd(b, a % b)
+        }
+    }
+
+    #[test]
+    fn example() {
+        let rs = shuffled("example.txt");
+        let groups = rs.infer_groups(3).unwrap().unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_valid(&rs, &groups);
+    }
+
+    #[test]
+    fn shuffled_input() {
+        let rs = shuffled("first.txt");
+        let groups = rs.infer_groups(3).unwrap().unwrap();
+        assert_eq!(groups.len(), 100);
+        assert_valid(&rs, &groups);
+    }
+
+    #[test]
+    fn no_partition() {
+        // Pairs share either both `a` and `b` or nothing at all
+        let rs = Rucksacks::parse("abcd\nabef\nabgh\nxyzw".as_bytes()).unwrap();
+        assert_eq!(rs.infer_groups(2).unwrap(), None);
+        let rs = Rucksacks::parse("abab\nacac\nadad\nxyxy\nxzxz\nwwww".as_bytes()).unwrap();
+        assert_eq!(rs.infer_groups(3).unwrap(), None);
+        assert!(rs.infer_groups(4).is_err());
+    }
+}
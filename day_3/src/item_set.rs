@@ -0,0 +1,103 @@
+use std::ops::{BitAnd, BitOr};
+
+/// Set of items stored as a bitmask. Bit `n` stands for the item at index `n` of the
+/// `PriorityTable`, so a table can hold at most 64 items.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    pub const CAPACITY: usize = 64;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, index: usize) {
+        assert!(index < Self::CAPACITY, "Item index {} out of range", index);
+        self.0 |= 1 << index;
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        index < Self::CAPACITY && self.0 & (1 << index) != 0
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn len(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Lowest index in the set
+    pub fn first(&self) -> Option<usize> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.0.trailing_zeros() as usize)
+        }
+    }
+
+    /// Indices in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = usize> {
+        let bits = self.0;
+        (0..Self::CAPACITY).filter(move |i| bits & (1 << i) != 0)
+    }
+}
+
+impl BitAnd for ItemSet {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        self.intersection(rhs)
+    }
+}
+
+impl BitOr for ItemSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+impl FromIterator<usize> for ItemSet {
+    fn from_iter<T: IntoIterator<Item = usize>>(iter: T) -> Self {
+        let mut set = Self::new();
+        iter.into_iter().for_each(|i| set.insert(i));
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_operations() {
+        let a: ItemSet = [0, 1, 2, 51].into_iter().collect();
+        let b: ItemSet = [2, 3, 51, 63].into_iter().collect();
+        assert_eq!(a.len(), 4);
+        assert!(a.contains(51));
+        assert!(!a.contains(63));
+        assert!(!a.contains(64));
+        assert_eq!((a & b).iter().collect::<Vec<_>>(), vec![2, 51]);
+        assert_eq!((a | b).iter().collect::<Vec<_>>(), vec![0, 1, 2, 3, 51, 63]);
+        assert_eq!((a & b).first(), Some(2));
+        assert_eq!(ItemSet::new().first(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_range() {
+        ItemSet::new().insert(64);
+    }
+}
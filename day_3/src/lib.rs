@@ -1,6 +1,15 @@
-pub mod second;
+pub mod analytics;
+pub mod inference;
+pub mod item_set;
+pub mod priority;
+#[cfg(test)]
+mod reference;
+pub mod repack;
 
 use anyhow::Result;
+use item_set::ItemSet;
+use priority::PriorityTable;
+use std::fmt;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
@@ -8,74 +17,197 @@ use std::io::{BufRead, BufReader};
 pub struct Rucksack {
     left: Vec<char>,
     right: Vec<char>,
+    /// Sets of the compartments, built once so that queries don't look items up again
+    left_set: ItemSet,
+    right_set: ItemSet,
+}
+pub struct Rucksacks {
+    rucksacks: Vec<Rucksack>,
+    table: PriorityTable,
 }
-pub struct Rucksacks(Vec<Rucksack>);
 
-impl From<(Vec<char>, Vec<char>)> for Rucksack {
-    fn from(c: (Vec<char>, Vec<char>)) -> Self {
-        Rucksack {
-            left: c.0,
-            right: c.1,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RucksackError {
+    /// Line holds no items. Blank lines aren't skipped, as they would shift the groups.
+    BlankLine { line: usize },
+    /// Line can't be split into two equally sized compartments
+    OddLength { line: usize, len: usize },
+    /// Item is missing from the priority table
+    InvalidItem { line: usize, item: char },
+    /// Rucksacks can't be split into groups of the given size
+    IncompleteGroup { rucksacks: usize, group_size: usize },
+    /// Rucksacks of the group starting at `first_line` have no item in common
+    NoBadge { first_line: usize },
+}
+
+impl fmt::Display for RucksackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BlankLine { line } => write!(f, "line {}: empty rucksack", line),
+            Self::OddLength { line, len } => {
+                write!(f, "line {}: odd number of items ({})", line, len)
+            }
+            Self::InvalidItem { line, item } => {
+                write!(f, "line {}: {:?} is not a valid item", line, item)
+            }
+            Self::NoBadge { first_line } => write!(
+                f,
+                "line {}: rucksacks of the group have no common item",
+                first_line
+            ),
+            Self::IncompleteGroup {
+                rucksacks,
+                group_size,
+            } => write!(
+                f,
+                "{} rucksacks can't be split into groups of {}",
+                rucksacks, group_size
+            ),
         }
     }
 }
 
+impl std::error::Error for RucksackError {}
+
 impl Rucksack {
-    pub fn find_duplication(&self) -> Option<char> {
-        assert_eq!(self.left.len(), self.right.len());
-        let mut i_l = 0;
-        let mut i_r = 0;
-        let len = self.left.len();
-
-        while i_l < len && i_r < len {
-            match self.left[i_l].cmp(&self.right[i_r]) {
-                std::cmp::Ordering::Less => i_l += 1,
-                std::cmp::Ordering::Equal => return Some(self.left[i_l]),
-                std::cmp::Ordering::Greater => i_r += 1,
-            }
+    /// Items missing from the table are left out of the sets.
+    pub fn new(left: Vec<char>, right: Vec<char>, table: &PriorityTable) -> Self {
+        Self {
+            left_set: table.set(&left),
+            right_set: table.set(&right),
+            left,
+            right,
+        }
+    }
+
+    fn parse(line: &str, line_no: usize, table: &PriorityTable) -> Result<Self, RucksackError> {
+        if line.trim().is_empty() {
+            return Err(RucksackError::BlankLine { line: line_no });
+        }
+        let items: Vec<char> = line.chars().collect();
+        if let Some(item) = items.iter().find(|c| table.index(**c).is_none()) {
+            return Err(RucksackError::InvalidItem {
+                line: line_no,
+                item: *item,
+            });
         }
-        None
+        if !items.len().is_multiple_of(2) {
+            return Err(RucksackError::OddLength {
+                line: line_no,
+                len: items.len(),
+            });
+        }
+        let (left, right) = items.split_at(items.len() / 2);
+        Ok(Self::new(left.to_vec(), right.to_vec(), table))
+    }
+
+    /// First duplicated item in the order of `table`, which has to be the one the rucksack was
+    /// built with
+    pub fn find_duplication(&self, table: &PriorityTable) -> Option<char> {
+        table.first(self.duplications())
+    }
+
+    /// Items present in both compartments
+    pub fn duplications(&self) -> ItemSet {
+        self.left_set & self.right_set
+    }
+
+    pub fn left_set(&self) -> ItemSet {
+        self.left_set
+    }
+
+    pub fn right_set(&self) -> ItemSet {
+        self.right_set
+    }
+
+    pub fn item_set(&self) -> ItemSet {
+        self.left_set | self.right_set
     }
 }
 
 impl Rucksacks {
     pub fn new(file: &str) -> Result<Self> {
         let file = File::open(file)?;
-        let reader = BufReader::new(file);
-        let rucksacks: Vec<Rucksack> = reader
+        Self::parse(BufReader::new(file))
+    }
+
+    /// Reads rucksacks holding items from a custom priority table.
+    pub fn with_table(file: &str, table: PriorityTable) -> Result<Self> {
+        let file = File::open(file)?;
+        Self::parse_with_table(BufReader::new(file), table)
+    }
+
+    /// Parses one rucksack per line. Line numbers in errors are counted from 1.
+    pub fn parse(reader: impl BufRead) -> Result<Self> {
+        Self::parse_with_table(reader, PriorityTable::default())
+    }
+
+    pub fn parse_with_table(reader: impl BufRead, table: PriorityTable) -> Result<Self> {
+        let rucksacks = reader
             .lines()
-            .filter_map(|l| l.ok())
-            .map(|mut l| {
-                let r = l.split_off(l.len() / 2);
+            .enumerate()
+            .map(|(i, l)| Ok(Rucksack::parse(&l?, i + 1, &table)?))
+            .collect::<Result<Vec<Rucksack>>>()?;
+        Ok(Self { rucksacks, table })
+    }
 
-                let mut l: Vec<_> = l.chars().collect();
-                let mut r: Vec<_> = r.chars().collect();
-                r.sort();
-                l.sort();
+    pub fn table(&self) -> &PriorityTable {
+        &self.table
+    }
 
-                (l, r).into()
-            })
-            .collect();
-        Ok(Self(rucksacks))
+    fn priority(&self, item: char) -> u32 {
+        self.table
+            .priority(item)
+            .expect("Items are validated while parsing")
     }
 
     // First solution
     pub fn calc_prio(&self) -> u32 {
-        let uppercase_a = 'A' as u32 - 1;
-        let lowercase_a = 'a' as u32 - 1;
-
-        self.0
+        self.rucksacks
             .iter()
-            .filter_map(|rs| rs.find_duplication())
-            .map(|c| {
-                if c >= 'a' {
-                    c as u32 - lowercase_a
-                } else {
-                    c as u32 - uppercase_a + 26
-                }
-            })
+            .filter_map(|rs| rs.find_duplication(&self.table))
+            .map(|c| self.priority(c))
             .sum()
     }
+
+    /// Splits consecutive rucksacks into groups of `group_size`.
+    pub fn groups(&self, group_size: usize) -> Result<Vec<&[Rucksack]>, RucksackError> {
+        let rucksacks = self.rucksacks.len();
+        if group_size == 0 || !rucksacks.is_multiple_of(group_size) {
+            return Err(RucksackError::IncompleteGroup {
+                rucksacks,
+                group_size,
+            });
+        }
+        Ok(self.rucksacks.chunks(group_size).collect())
+    }
+
+    /// Finds the item shared by all rucksacks of every group.
+    pub fn badges(&self, group_size: usize) -> Result<Vec<char>, RucksackError> {
+        self.groups(group_size)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, group)| {
+                group
+                    .iter()
+                    .map(|r| r.item_set())
+                    .reduce(|a, b| a & b)
+                    .and_then(|common| self.table.first(common))
+                    .ok_or(RucksackError::NoBadge {
+                        first_line: i * group_size + 1,
+                    })
+            })
+            .collect()
+    }
+
+    // Second solution
+    pub fn calc_badge_prio(&self, group_size: usize) -> Result<u32, RucksackError> {
+        Ok(self
+            .badges(group_size)?
+            .into_iter()
+            .map(|c| self.priority(c))
+            .sum())
+    }
 }
 
 #[cfg(test)]
@@ -93,4 +225,103 @@ mod tests {
         let rs = Rucksacks::new("first.txt").unwrap();
         assert_eq!(rs.calc_prio(), 7903);
     }
+
+    #[test]
+    fn example_badges() {
+        let rs = Rucksacks::new("example.txt").unwrap();
+        assert_eq!(rs.badges(3).unwrap(), vec!['r', 'Z']);
+        assert_eq!(rs.calc_badge_prio(3).unwrap(), 70);
+    }
+
+    #[test]
+    fn second() {
+        let rs = Rucksacks::new("first.txt").unwrap();
+        assert_eq!(rs.calc_badge_prio(3).unwrap(), 2548);
+    }
+
+    #[test]
+    fn other_group_sizes() {
+        let rs = Rucksacks::new("example.txt").unwrap();
+        assert_eq!(rs.badges(2).unwrap().len(), 3);
+        assert_eq!(rs.badges(6), Err(RucksackError::NoBadge { first_line: 1 }));
+        assert_eq!(
+            rs.badges(4),
+            Err(RucksackError::IncompleteGroup {
+                rucksacks: 6,
+                group_size: 4
+            })
+        );
+        assert!(rs.badges(0).is_err());
+    }
+
+    fn parse_error(input: &str) -> RucksackError {
+        let err = Rucksacks::parse(input.as_bytes()).err().unwrap();
+        err.downcast::<RucksackError>().unwrap()
+    }
+
+    #[test]
+    fn invalid_rucksacks() {
+        assert_eq!(
+            parse_error("abab\nabcab"),
+            RucksackError::OddLength { line: 2, len: 5 }
+        );
+        assert_eq!(
+            parse_error("ab1b"),
+            RucksackError::InvalidItem { line: 1, item: '1' }
+        );
+        assert_eq!(
+            parse_error("abab\naa bb"),
+            RucksackError::InvalidItem { line: 2, item: ' ' }
+        );
+        assert_eq!(
+            parse_error("aaaa\naąaa"),
+            RucksackError::InvalidItem {
+                line: 2, item: 'ą'
+            }
+        );
+        assert_eq!(
+            parse_error("abab\n\nacac"),
+            RucksackError::BlankLine { line: 2 }
+        );
+        assert_eq!(
+            parse_error("abab\n  "),
+            RucksackError::BlankLine { line: 2 }
+        );
+    }
+
+    #[test]
+    fn uneven_compartments() {
+        let table = PriorityTable::default();
+        let rucksack = Rucksack::new(vec!['a', 'b', 'c'], vec!['c'], &table);
+        assert_eq!(rucksack.find_duplication(&table), Some('c'));
+        let rucksack = Rucksack::new(vec![], vec!['a'], &table);
+        assert_eq!(rucksack.find_duplication(&table), None);
+    }
+
+    #[test]
+    fn custom_table() {
+        let table = PriorityTable::new([('1', 5), ('2', 7), ('ł', 100), ('x', 1)]).unwrap();
+        let rs = Rucksacks::parse_with_table("1ł2ł\n2ł12\nłłxx".as_bytes(), table).unwrap();
+        assert_eq!(rs.calc_prio(), 100 + 7);
+        assert_eq!(rs.badges(3).unwrap(), vec!['ł']);
+        assert_eq!(rs.calc_badge_prio(3).unwrap(), 100);
+
+        let table = PriorityTable::from_alphabet("ab").unwrap();
+        let err = Rucksacks::parse_with_table("abac".as_bytes(), table)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.downcast::<RucksackError>().unwrap(),
+            RucksackError::InvalidItem { line: 1, item: 'c' }
+        );
+    }
+
+    #[test]
+    fn missing_badge() {
+        let rs = Rucksacks::parse("abab\nacac\nadad\nabab\nacac\nXdXd".as_bytes()).unwrap();
+        assert_eq!(
+            rs.calc_badge_prio(3),
+            Err(RucksackError::NoBadge { first_line: 4 })
+        );
+    }
 }
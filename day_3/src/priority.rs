@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use anyhow::Result;
+
+use crate::item_set::ItemSet;
+
+/// Maps items to their priorities. The position of an item in the table is its bit in `ItemSet`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriorityTable {
+    items: Vec<char>,
+    weights: Vec<u32>,
+    indices: HashMap<char, usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableError {
+    DuplicateItem(char),
+    TooManyItems(usize),
+    /// Line of a table file is not `<item> <weight>`
+    Malformed {
+        line: usize,
+        content: String,
+    },
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateItem(c) => write!(f, "item {:?} is declared twice", c),
+            Self::TooManyItems(n) => write!(
+                f,
+                "table can hold at most {} items, got {}",
+                ItemSet::CAPACITY,
+                n
+            ),
+            Self::Malformed { line, content } => {
+                write!(
+                    f,
+                    "line {}: expected item and weight, got {:?}",
+                    line, content
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+impl PriorityTable {
+    /// Creates table from `(item, priority)` pairs. Items keep the given order.
+    pub fn new(items: impl IntoIterator<Item = (char, u32)>) -> Result<Self, TableError> {
+        let mut table = Self {
+            items: vec![],
+            weights: vec![],
+            indices: HashMap::new(),
+        };
+        for (item, weight) in items {
+            if table.indices.insert(item, table.items.len()).is_some() {
+                return Err(TableError::DuplicateItem(item));
+            }
+            table.items.push(item);
+            table.weights.push(weight);
+        }
+        if table.items.len() > ItemSet::CAPACITY {
+            return Err(TableError::TooManyItems(table.items.len()));
+        }
+        Ok(table)
+    }
+
+    /// Items get priorities 1, 2, 3... in the order of the alphabet.
+    pub fn from_alphabet(alphabet: &str) -> Result<Self, TableError> {
+        Self::new(alphabet.chars().zip(1..))
+    }
+
+    /// Parses table with one `<item> <weight>` pair per line. Blank lines are skipped.
+    pub fn parse(reader: impl BufRead) -> Result<Self> {
+        let mut items = vec![];
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let malformed = || TableError::Malformed {
+                line: i + 1,
+                content: line.clone(),
+            };
+            let mut parts = line.split_whitespace();
+            let (Some(item), Some(weight), None) = (parts.next(), parts.next(), parts.next())
+            else {
+                return Err(malformed().into());
+            };
+            let mut chars = item.chars();
+            let (Some(item), None) = (chars.next(), chars.next()) else {
+                return Err(malformed().into());
+            };
+            let weight = weight.parse().map_err(|_| malformed())?;
+            items.push((item, weight));
+        }
+        Ok(Self::new(items)?)
+    }
+
+    pub fn load(file: &str) -> Result<Self> {
+        let file = File::open(file)?;
+        Self::parse(BufReader::new(file))
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn index(&self, item: char) -> Option<usize> {
+        self.indices.get(&item).copied()
+    }
+
+    pub fn item(&self, index: usize) -> char {
+        self.items[index]
+    }
+
+    pub fn priority(&self, item: char) -> Option<u32> {
+        self.index(item).map(|i| self.weights[i])
+    }
+
+    /// Builds set of items. Items missing from the table are skipped.
+    pub fn set<'a>(&self, items: impl IntoIterator<Item = &'a char>) -> ItemSet {
+        items.into_iter().filter_map(|c| self.index(*c)).collect()
+    }
+
+    /// Items of the set in the table order
+    pub fn items(&self, set: ItemSet) -> impl Iterator<Item = char> + '_ {
+        set.iter().map(|i| self.items[i])
+    }
+
+    /// First item of the set in the table order
+    pub fn first(&self, set: ItemSet) -> Option<char> {
+        set.first().map(|i| self.items[i])
+    }
+
+    /// Sum of priorities of all items in the set
+    pub fn sum(&self, set: ItemSet) -> u32 {
+        set.iter().map(|i| self.weights[i]).sum()
+    }
+}
+
+/// Puzzle priorities: `a..=z` are 1-26 and `A..=Z` are 27-52.
+impl Default for PriorityTable {
+    fn default() -> Self {
+        let alphabet: String = ('a'..='z').chain('A'..='Z').collect();
+        Self::from_alphabet(&alphabet).expect("Puzzle alphabet is valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn puzzle_priorities() {
+        let table = PriorityTable::default();
+        assert_eq!(table.len(), 52);
+        assert_eq!(table.priority('a'), Some(1));
+        assert_eq!(table.priority('z'), Some(26));
+        assert_eq!(table.priority('A'), Some(27));
+        assert_eq!(table.priority('Z'), Some(52));
+        assert_eq!(table.priority('1'), None);
+
+        let set = table.set(&['Z', 'b', 'A', 'b']);
+        assert_eq!(table.items(set).collect::<String>(), "bAZ");
+        assert_eq!(table.first(set), Some('b'));
+        assert_eq!(table.sum(set), 2 + 27 + 52);
+    }
+
+    #[test]
+    fn custom_tables() {
+        let table = PriorityTable::parse("7 10\n\nż 3\n# 100\n".as_bytes()).unwrap();
+        assert_eq!(table.priority('ż'), Some(3));
+        assert_eq!(table.priority('#'), Some(100));
+        assert_eq!(table.first(table.set(&['#', 'ż'])), Some('ż'));
+
+        assert_eq!(
+            PriorityTable::from_alphabet("abca"),
+            Err(TableError::DuplicateItem('a'))
+        );
+        let alphabet: String = ('\u{100}'..'\u{141}').collect();
+        assert_eq!(
+            PriorityTable::from_alphabet(&alphabet),
+            Err(TableError::TooManyItems(65))
+        );
+    }
+
+    #[test]
+    fn malformed_table() {
+        for (input, line) in [("a 1\nbc 2", 2), ("a", 1), ("a 1 2", 1), ("a -1", 1)] {
+            let err = PriorityTable::parse(input.as_bytes()).unwrap_err();
+            let TableError::Malformed { line: l, .. } = err.downcast::<TableError>().unwrap()
+            else {
+                panic!("Expected malformed line error for {:?}", input);
+            };
+            assert_eq!(l, line);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{Rucksack, RucksackError, Rucksacks};
+
+/// Finds items present in both sorted slices.
+///
+/// Sorted vector implementation kept as a reference for the `ItemSet` based one.
+fn find_duplication(l: &[char], r: &[char]) -> Vec<char> {
+    let l_len = l.len();
+    let r_len = r.len();
+    let mut i_l = 0;
+    let mut i_r = 0;
+
+    let mut duplications = vec![];
+
+    while i_l < l_len && i_r < r_len {
+        match l[i_l].cmp(&r[i_r]) {
+            std::cmp::Ordering::Less => i_l += 1,
+            std::cmp::Ordering::Equal => {
+                duplications.push(l[i_l]);
+                i_l += 1;
+                i_r += 1;
+            }
+            std::cmp::Ordering::Greater => i_r += 1,
+        }
+    }
+
+    duplications
+}
+
+fn sorted(items: impl IntoIterator<Item = char>) -> Vec<char> {
+    let mut items: Vec<_> = items.into_iter().collect();
+    items.sort();
+    items.dedup();
+    items
+}
+
+impl Rucksack {
+    /// Items present in both compartments, sorted
+    fn duplications_sorted(&self) -> Vec<char> {
+        find_duplication(
+            &sorted(self.left.iter().copied()),
+            &sorted(self.right.iter().copied()),
+        )
+    }
+}
+
+impl Rucksacks {
+    /// Items shared by all rucksacks of every group, sorted
+    fn badges_sorted(&self, group_size: usize) -> Result<Vec<Vec<char>>, RucksackError> {
+        Ok(self
+            .groups(group_size)?
+            .into_iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|r| sorted(r.left.iter().chain(&r.right).copied()))
+                    .reduce(|a, b| find_duplication(&a, &b))
+                    .unwrap_or_default()
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item_set::ItemSet;
+    use crate::priority::PriorityTable;
+
+    fn set_items(rucksacks: &Rucksacks, set: ItemSet) -> Vec<char> {
+        sorted(rucksacks.table.items(set))
+    }
+
+    /// Rucksacks with random letters, generated with a fixed seed
+    fn random_rucksacks(count: usize, len: usize) -> Rucksacks {
+        let letters: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+        let mut state = 0x2022_u64;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            letters[(state >> 33) as usize % letters.len()]
+        };
+        let table = PriorityTable::default();
+        Rucksacks {
+            rucksacks: (0..count)
+                .map(|_| {
+                    let left = (0..len).map(|_| next()).collect();
+                    let right = (0..len).map(|_| next()).collect();
+                    Rucksack::new(left, right, &table)
+                })
+                .collect(),
+            table,
+        }
+    }
+
+    fn assert_same(rucksacks: &Rucksacks, group_size: usize) {
+        for rucksack in &rucksacks.rucksacks {
+            assert_eq!(
+                set_items(rucksacks, rucksack.duplications()),
+                rucksack.duplications_sorted()
+            );
+        }
+        let badges = rucksacks.badges_sorted(group_size).unwrap();
+        let groups = rucksacks.groups(group_size).unwrap();
+        for (group, badges) in groups.iter().zip(badges) {
+            let common = group
+                .iter()
+                .map(|r| r.item_set())
+                .reduce(|a, b| a & b)
+                .unwrap();
+            assert_eq!(set_items(rucksacks, common), badges);
+        }
+    }
+
+    #[test]
+    fn puzzle_inputs() {
+        assert_same(&Rucksacks::new("example.txt").unwrap(), 3);
+        assert_same(&Rucksacks::new("first.txt").unwrap(), 3);
+    }
+
+    #[test]
+    fn random_inputs() {
+        assert_same(&random_rucksacks(300, 4), 3);
+        assert_same(&random_rucksacks(100, 20), 4);
+        assert_same(&random_rucksacks(60, 30), 2);
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+
+use crate::priority::PriorityTable;
+use crate::{Rucksack, Rucksacks};
+
+/// Exchange of one item from the left compartment with one item from the right one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Swap {
+    pub left: char,
+    pub right: char,
+}
+
+/// Swaps for every rucksack of a file. `None` marks rucksacks which can't be made disjoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepackPlan(pub Vec<Option<Vec<Swap>>>);
+
+impl RepackPlan {
+    pub fn total_swaps(&self) -> usize {
+        self.0.iter().flatten().map(|s| s.len()).sum()
+    }
+
+    /// Lines, counted from 1, of rucksacks which can't be repacked
+    pub fn impossible_lines(&self) -> Vec<usize> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.is_none())
+            .map(|(i, _)| i + 1)
+            .collect()
+    }
+}
+
+impl Rucksack {
+    /// Finds the minimum number of swaps after which no item type is in both compartments.
+    ///
+    /// Every item type has to end up in a single compartment, so the types kept on the left have
+    /// to add up exactly to the compartment size. Among such choices the one moving the fewest
+    /// items wins. Returns `None` if the item counts can't be split that way.
+    pub fn plan_repack(&self) -> Option<Vec<Swap>> {
+        let size = self.left.len();
+        // (item, count on the left, count on the right)
+        let mut counts: BTreeMap<char, (usize, usize)> = BTreeMap::new();
+        self.left
+            .iter()
+            .for_each(|c| counts.entry(*c).or_default().0 += 1);
+        self.right
+            .iter()
+            .for_each(|c| counts.entry(*c).or_default().1 += 1);
+        let counts: Vec<_> = counts.into_iter().map(|(c, (l, r))| (c, l, r)).collect();
+
+        // cost[k][s] is the minimal number of items moved to the left when the first k types
+        // placed on the left hold s items
+        let mut cost = vec![vec![None; size + 1]; counts.len() + 1];
+        cost[0][0] = Some(0);
+        for (k, (_, l, r)) in counts.iter().enumerate() {
+            for s in 0..=size {
+                let Some(current) = cost[k][s] else {
+                    continue;
+                };
+                // Type goes to the right compartment
+                cost[k + 1][s] = min(cost[k + 1][s], current);
+                // Type goes to the left compartment
+                if s + l + r <= size {
+                    cost[k + 1][s + l + r] = min(cost[k + 1][s + l + r], current + r);
+                }
+            }
+        }
+        cost[counts.len()][size]?;
+
+        let mut to_left = vec![];
+        let mut to_right = vec![];
+        let mut s = size;
+        for k in (0..counts.len()).rev() {
+            let (item, l, r) = counts[k];
+            let stays_right = cost[k][s].is_some_and(|c| Some(c) == cost[k + 1][s]);
+            if stays_right {
+                to_right.extend(std::iter::repeat_n(item, l));
+            } else {
+                to_left.extend(std::iter::repeat_n(item, r));
+                s -= l + r;
+            }
+        }
+
+        Some(
+            to_right
+                .into_iter()
+                .zip(to_left)
+                .map(|(left, right)| Swap { left, right })
+                .collect(),
+        )
+    }
+
+    /// Returns the rucksack after the swaps, keeping positions of items which didn't move.
+    /// `table` is the one the rucksack was built with.
+    pub fn repacked(&self, swaps: &[Swap], table: &PriorityTable) -> Rucksack {
+        let mut left = self.left.clone();
+        let mut right = self.right.clone();
+        for swap in swaps {
+            let l = left
+                .iter()
+                .position(|c| *c == swap.left)
+                .expect("Swapped item is in the left compartment");
+            let r = right
+                .iter()
+                .position(|c| *c == swap.right)
+                .expect("Swapped item is in the right compartment");
+            left[l] = swap.right;
+            right[r] = swap.left;
+        }
+        Rucksack::new(left, right, table)
+    }
+}
+
+fn min(a: Option<usize>, b: usize) -> Option<usize> {
+    Some(a.map_or(b, |a| a.min(b)))
+}
+
+impl Rucksacks {
+    pub fn plan_repack(&self) -> RepackPlan {
+        RepackPlan(self.rucksacks.iter().map(|r| r.plan_repack()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rucksack(line: &str) -> Rucksack {
+        let (l, r) = line.split_at(line.len() / 2);
+        Rucksack::new(
+            l.chars().collect(),
+            r.chars().collect(),
+            &PriorityTable::default(),
+        )
+    }
+
+    fn assert_disjoint(rucksack: &Rucksack, swaps: &[Swap]) {
+        let repacked = rucksack.repacked(swaps, &PriorityTable::default());
+        assert!(repacked.left.iter().all(|c| !repacked.right.contains(c)));
+        assert!(repacked.duplications().is_empty());
+        let mut before = rucksack.left.clone();
+        before.extend(&rucksack.right);
+        before.sort();
+        let mut after = repacked.left.clone();
+        after.extend(&repacked.right);
+        after.sort();
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn minimal_swaps() {
+        let r = rucksack("abab");
+        let swaps = r.plan_repack().unwrap();
+        assert_eq!(swaps.len(), 1);
+        assert_disjoint(&r, &swaps);
+
+        // Either both `a`s from the right or the `b` and `c` from the right have to move
+        let r = rucksack("aabbcbaa");
+        let swaps = r.plan_repack().unwrap();
+        assert_eq!(swaps.len(), 2);
+        assert_disjoint(&r, &swaps);
+
+        let r = rucksack("abcdaefg");
+        let swaps = r.plan_repack().unwrap();
+        assert_eq!(swaps.len(), 1);
+        assert_disjoint(&r, &swaps);
+
+        let r = rucksack("abcdefgh");
+        assert_eq!(r.plan_repack(), Some(vec![]));
+    }
+
+    #[test]
+    fn impossible() {
+        assert_eq!(rucksack("aaab").plan_repack(), None);
+        assert_eq!(rucksack("abcabc").plan_repack(), None);
+    }
+
+    #[test]
+    fn file_plan() {
+        let rs = Rucksacks::parse("abab\naaab\nabcd\naabbcbaa".as_bytes()).unwrap();
+        let plan = rs.plan_repack();
+        assert_eq!(plan.total_swaps(), 3);
+        assert_eq!(plan.impossible_lines(), vec![2]);
+
+        let rs = Rucksacks::new("first.txt").unwrap();
+        let plan = rs.plan_repack();
+        for (rucksack, swaps) in rs.rucksacks.iter().zip(&plan.0) {
+            if let Some(swaps) = swaps {
+                assert_disjoint(rucksack, swaps);
+            }
+        }
+    }
+}
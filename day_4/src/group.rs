@@ -0,0 +1,184 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    str::FromStr,
+};
+
+use anyhow::Result;
+
+use crate::interval::Interval;
+use crate::{ParseError, ParseErrorKind, SectionPair};
+
+/// Ranges assigned to a crew of any size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssignmentGroup(pub Vec<Interval>);
+
+impl AssignmentGroup {
+    /// Ranges ordered by their first section, longer ones first
+    fn sorted(&self) -> Vec<(usize, Interval)> {
+        let mut sorted: Vec<_> = self.0.iter().copied().enumerate().collect();
+        sorted.sort_by_key(|(_, i)| (i.first(), std::cmp::Reverse(i.last())));
+        sorted
+    }
+
+    /// Checks if any range lies entirely within another one
+    pub fn any_contained(&self) -> bool {
+        let mut max_last = None;
+        for (_, interval) in self.sorted() {
+            if max_last.is_some_and(|last| interval.last() <= last) {
+                return true;
+            }
+            max_last = max_last.max(Some(interval.last()));
+        }
+        false
+    }
+
+    /// Checks if any two ranges overlap
+    pub fn any_overlap(&self) -> bool {
+        self.sorted().windows(2).any(|w| w[0].1.overlaps(&w[1].1))
+    }
+
+    /// Sections assigned to every member of the crew
+    pub fn common(&self) -> Option<Interval> {
+        let (first, rest) = self.0.split_first()?;
+        rest.iter()
+            .try_fold(*first, |common, interval| common.intersection(interval))
+    }
+
+    /// Picks the fewest ranges which together cover every section of the group. Returns indices of
+    /// the chosen ranges.
+    ///
+    /// Greedy: from the first uncovered section, the range starting there or earlier and reaching
+    /// the furthest is always a safe choice.
+    pub fn minimal_cover(&self) -> Vec<usize> {
+        let sorted = self.sorted();
+        let mut chosen = vec![];
+        let mut i = 0;
+        // First section not covered yet
+        let mut uncovered: Option<u128> = None;
+        while i < sorted.len() {
+            let from = match uncovered {
+                Some(u) if u > sorted[i].1.first() as u128 => u,
+                // Gap between the components of the union, start over from the next range
+                _ => sorted[i].1.first() as u128,
+            };
+            let mut best: Option<(usize, Interval)> = None;
+            while i < sorted.len() && sorted[i].1.first() as u128 <= from {
+                if best.is_none_or(|(_, b)| sorted[i].1.end() > b.end()) {
+                    best = Some(sorted[i]);
+                }
+                i += 1;
+            }
+            let (index, interval) =
+                best.expect("At least one range starts at the uncovered section");
+            if interval.end() > from {
+                chosen.push(index);
+                uncovered = Some(interval.end());
+            }
+        }
+        chosen.sort();
+        chosen
+    }
+}
+
+impl From<&SectionPair> for AssignmentGroup {
+    fn from(pair: &SectionPair) -> Self {
+        Self(vec![pair.left, pair.right])
+    }
+}
+
+impl FromStr for AssignmentGroup {
+    type Err = ParseErrorKind;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|r| r.parse())
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+/// Parses one group of comma separated ranges per line, skipping blank lines.
+pub fn parse_groups(reader: impl BufRead) -> Result<Vec<AssignmentGroup>> {
+    let mut groups = vec![];
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let group = line
+            .parse()
+            .map_err(|kind| ParseError { line: i + 1, kind })?;
+        groups.push(group);
+    }
+    Ok(groups)
+}
+
+pub fn load_groups(file: &str) -> Result<Vec<AssignmentGroup>> {
+    let file = File::open(file)?;
+    parse_groups(BufReader::new(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::load_data;
+
+    fn group(s: &str) -> AssignmentGroup {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn pairs_agree_with_section_pairs() {
+        for file in ["example.txt", "test_1.txt"] {
+            let sections = load_data(file).unwrap();
+            let groups = load_groups(file).unwrap();
+            for (pair, group) in sections.0.iter().zip(&groups) {
+                assert_eq!(pair.contains(), group.any_contained());
+                assert_eq!(pair.overlaps(), group.any_overlap());
+                assert_eq!(pair.overlap(), group.common());
+            }
+        }
+    }
+
+    #[test]
+    fn crews() {
+        let g = group("1-3,5-9,2-6");
+        assert!(!g.any_contained());
+        assert!(g.any_overlap());
+        assert_eq!(g.common(), None);
+        assert_eq!(g.minimal_cover(), vec![0, 1, 2]);
+
+        let g = group("1-10,2-4,3-12,11-15,3-3");
+        assert!(g.any_contained());
+        assert_eq!(g.common(), None);
+        assert_eq!(g.minimal_cover(), vec![0, 3]);
+        assert_eq!(group("1-10,2-4,3-12,3-3").common(), Interval::closed(3, 3));
+
+        let g = group("1-2,5-6,7-8");
+        assert!(!g.any_overlap());
+        assert_eq!(g.minimal_cover(), vec![0, 1, 2]);
+
+        let g = group("4-4");
+        assert!(!g.any_contained());
+        assert!(!g.any_overlap());
+        assert_eq!(g.common(), Interval::closed(4, 4));
+        assert_eq!(g.minimal_cover(), vec![0]);
+
+        let g = group("1-5,1-5");
+        assert!(g.any_contained());
+        assert_eq!(g.minimal_cover().len(), 1);
+    }
+
+    #[test]
+    fn invalid_group() {
+        let err = parse_groups("1-2,3-4,5-6\n1-2,,3-4".as_bytes()).unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError {
+                line: 2,
+                kind: ParseErrorKind::MalformedRange("".to_owned())
+            }
+        );
+    }
+}
@@ -0,0 +1,137 @@
+/// Non-empty range of section IDs.
+///
+/// Bounds are stored as a closed range `first..=last`. Half-open ranges `start..end` can be
+/// converted with `Interval::half_open` and `Interval::end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    first: u64,
+    last: u64,
+}
+
+impl Interval {
+    /// Range `first..=last`. Returns `None` if `first > last`.
+    pub fn closed(first: u64, last: u64) -> Option<Self> {
+        (first <= last).then_some(Self { first, last })
+    }
+
+    /// Range `start..end`. Returns `None` if the range is empty.
+    pub fn half_open(start: u64, end: u64) -> Option<Self> {
+        (start < end).then(|| Self {
+            first: start,
+            last: end - 1,
+        })
+    }
+
+    pub fn first(&self) -> u64 {
+        self.first
+    }
+
+    pub fn last(&self) -> u64 {
+        self.last
+    }
+
+    /// Exclusive end of the range. It doesn't fit `u64` when the range ends at `u64::MAX`.
+    pub fn end(&self) -> u128 {
+        self.last as u128 + 1
+    }
+
+    /// Number of sections in the range
+    #[allow(clippy::len_without_is_empty)] // Intervals are never empty
+    pub fn len(&self) -> u128 {
+        self.end() - self.first as u128
+    }
+
+    pub fn contains(&self, section: u64) -> bool {
+        self.first <= section && section <= self.last
+    }
+
+    /// Checks if `other` lies entirely within this range
+    pub fn contains_interval(&self, other: &Interval) -> bool {
+        self.first <= other.first && other.last <= self.last
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.first <= other.last && other.first <= self.last
+    }
+
+    /// Sections present in both ranges
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        Self::closed(self.first.max(other.first), self.last.min(other.last))
+    }
+
+    /// Sections present in any of the ranges. Returns `None` if there is a gap between them, as
+    /// the union wouldn't be a single range.
+    pub fn union(&self, other: &Interval) -> Option<Interval> {
+        let touching = self.first as u128 <= other.end() && other.first as u128 <= self.end();
+        touching.then(|| Self {
+            first: self.first.min(other.first),
+            last: self.last.max(other.last),
+        })
+    }
+
+    /// Sections of this range missing from `other`. Up to two ranges remain.
+    pub fn difference(&self, other: &Interval) -> Vec<Interval> {
+        if !self.overlaps(other) {
+            return vec![*self];
+        }
+        let before = Self::half_open(self.first, other.first);
+        let after = (other.last < self.last).then(|| Self {
+            first: other.last + 1,
+            last: self.last,
+        });
+        before.into_iter().chain(after).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(first: u64, last: u64) -> Interval {
+        Interval::closed(first, last).unwrap()
+    }
+
+    #[test]
+    fn semantics() {
+        assert_eq!(Interval::closed(3, 7), Interval::half_open(3, 8));
+        assert_eq!(Interval::closed(7, 3), None);
+        assert_eq!(Interval::half_open(3, 3), None);
+        assert_eq!(interval(3, 7).len(), 5);
+        assert_eq!(interval(3, 7).end(), 8);
+        assert_eq!(interval(6, 6).len(), 1);
+        assert_eq!(interval(0, u64::MAX).len(), 1 << 64);
+        assert!(interval(3, 7).contains(7));
+        assert!(!interval(3, 7).contains(8));
+    }
+
+    #[test]
+    fn intersection_and_union() {
+        let a = interval(2, 6);
+        let b = interval(4, 8);
+        assert_eq!(a.intersection(&b), Some(interval(4, 6)));
+        assert_eq!(a.union(&b), Some(interval(2, 8)));
+        assert_eq!(a.intersection(&interval(7, 9)), None);
+        // Adjacent ranges merge without a gap
+        assert_eq!(a.union(&interval(7, 9)), Some(interval(2, 9)));
+        assert_eq!(a.union(&interval(8, 9)), None);
+        assert!(a.contains_interval(&interval(3, 6)));
+        assert!(!a.contains_interval(&b));
+    }
+
+    #[test]
+    fn difference() {
+        let a = interval(2, 8);
+        assert_eq!(
+            a.difference(&interval(4, 5)),
+            vec![interval(2, 3), interval(6, 8)]
+        );
+        assert_eq!(a.difference(&interval(0, 5)), vec![interval(6, 8)]);
+        assert_eq!(a.difference(&interval(5, 10)), vec![interval(2, 4)]);
+        assert_eq!(a.difference(&interval(1, 9)), vec![]);
+        assert_eq!(a.difference(&interval(9, 10)), vec![a]);
+        assert_eq!(
+            interval(0, u64::MAX).difference(&interval(0, u64::MAX)),
+            vec![]
+        );
+    }
+}
@@ -1,13 +1,22 @@
+pub mod group;
+pub mod interval;
+pub mod rebalance;
+pub mod sweep;
+
 use anyhow::Result;
+use interval::Interval;
 use std::{
+    fmt,
     fs::File,
     io::{BufRead, BufReader},
+    num::IntErrorKind,
+    str::FromStr,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SectionPair {
-    pub left: (u32, u32),
-    pub right: (u32, u32),
+    pub left: Interval,
+    pub right: Interval,
 }
 
 pub struct Sections(Vec<SectionPair>);
@@ -16,13 +25,22 @@ impl SectionPair {
     /// Checks if one of the sections contains the other one
     pub fn contains(&self) -> bool {
         let SectionPair { left, right } = self;
-        left.0 <= right.0 && right.1 <= left.1 || left.0 >= right.0 && right.1 >= left.1
+        left.contains_interval(right) || right.contains_interval(left)
     }
 
     /// Checks if pairs overlap
     pub fn overlaps(&self) -> bool {
-        let SectionPair { left, right } = self;
-        left.0 <= right.1 && right.0 <= left.1
+        self.left.overlaps(&self.right)
+    }
+
+    /// Sections assigned to both elves
+    pub fn overlap(&self) -> Option<Interval> {
+        self.left.intersection(&self.right)
+    }
+
+    /// Number of sections assigned to both elves
+    pub fn overlap_len(&self) -> u128 {
+        self.overlap().map_or(0, |o| o.len())
     }
 }
 
@@ -40,31 +58,113 @@ impl Sections {
     pub fn count_overlaps(&self) -> usize {
         self.0.iter().filter(|s| s.overlaps()).count()
     }
+
+    /// Total number of sections assigned twice within the pairs
+    pub fn duplicated_work(&self) -> u128 {
+        self.0.iter().map(|s| s.overlap_len()).sum()
+    }
 }
 
-pub fn load_data(file: &str) -> Result<Sections> {
-    let file = File::open(file)?;
-    let reader = BufReader::new(file);
-    let pair: Sections = reader
-        .lines()
-        .filter_map(|l| l.ok())
-        .map(|l| -> Result<_> {
-            let borders: Vec<_> = l
-                .split([',', '-'])
-                .map(|s| s.trim().parse::<u32>())
-                .filter_map(|s| s.ok())
-                .collect();
-            assert_eq!(borders.len(), 4);
-
-            Ok(SectionPair {
-                left: (borders[0], borders[1]),
-                right: (borders[2], borders[3]),
-            })
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// Line doesn't hold exactly two ranges
+    Arity(usize),
+    /// Range is not written as `first-last`
+    MalformedRange(String),
+    InvalidNumber(String),
+    /// Section ID doesn't fit in 64 bits
+    Overflow(String),
+    /// Range like `7-3` ending before it starts
+    Reversed {
+        first: u64,
+        last: u64,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line number counted from 1
+    pub line: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Arity(n) => write!(f, "expected two ranges, got {}", n),
+            Self::MalformedRange(r) => write!(f, "expected range as first-last, got {:?}", r),
+            Self::InvalidNumber(n) => write!(f, "invalid section ID {:?}", n),
+            Self::Overflow(n) => write!(f, "section ID {} is too large", n),
+            Self::Reversed { first, last } => {
+                write!(f, "range {}-{} ends before it starts", first, last)
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn parse_section(s: &str) -> Result<u64, ParseErrorKind> {
+    let s = s.trim();
+    s.parse()
+        .map_err(|e: std::num::ParseIntError| match e.kind() {
+            IntErrorKind::PosOverflow => ParseErrorKind::Overflow(s.to_owned()),
+            _ => ParseErrorKind::InvalidNumber(s.to_owned()),
+        })
+}
+
+impl FromStr for Interval {
+    type Err = ParseErrorKind;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (first, last) = s
+            .split_once('-')
+            .ok_or_else(|| ParseErrorKind::MalformedRange(s.trim().to_owned()))?;
+        let (first, last) = (parse_section(first)?, parse_section(last)?);
+        Interval::closed(first, last).ok_or(ParseErrorKind::Reversed { first, last })
+    }
+}
+
+impl FromStr for SectionPair {
+    type Err = ParseErrorKind;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ranges: Vec<&str> = s.split(',').collect();
+        let [left, right] = ranges[..] else {
+            return Err(ParseErrorKind::Arity(ranges.len()));
+        };
+        Ok(SectionPair {
+            left: left.parse()?,
+            right: right.parse()?,
         })
-        .filter_map(|l| l.ok())
-        .collect();
+    }
+}
+
+/// Parses one pair of ranges per line, skipping blank lines.
+pub fn parse_sections(reader: impl BufRead) -> Result<Sections> {
+    let mut pairs = vec![];
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let pair = line
+            .parse()
+            .map_err(|kind| ParseError { line: i + 1, kind })?;
+        pairs.push(pair);
+    }
+    Ok(Sections(pairs))
+}
 
-    Ok(pair)
+pub fn load_data(file: &str) -> Result<Sections> {
+    let file = File::open(file)?;
+    parse_sections(BufReader::new(file))
 }
 
 #[cfg(test)]
@@ -94,4 +194,53 @@ mod tests {
         let sections = load_data("test_1.txt").unwrap();
         assert_eq!(sections.count_overlaps(), 779)
     }
+
+    #[test]
+    fn example_overlap_size() {
+        let sections = load_data("example.txt").unwrap();
+        let overlaps: Vec<_> = sections.0.iter().map(|s| s.overlap()).collect();
+        assert_eq!(overlaps[0], None);
+        assert_eq!(overlaps[2], Interval::closed(7, 7));
+        assert_eq!(overlaps[3], Interval::closed(3, 7));
+        assert_eq!(sections.duplicated_work(), 1 + 5 + 1 + 3);
+    }
+
+    fn parse_error(input: &str) -> ParseError {
+        let err = parse_sections(input.as_bytes()).err().unwrap();
+        err.downcast::<ParseError>().unwrap()
+    }
+
+    #[test]
+    fn invalid_lines() {
+        let cases = [
+            ("2-4,6-8\n2-4", 2, ParseErrorKind::Arity(1)),
+            ("2-4,6-8,1-2", 1, ParseErrorKind::Arity(3)),
+            ("2-4,68", 1, ParseErrorKind::MalformedRange("68".to_owned())),
+            ("2-4,6-x", 1, ParseErrorKind::InvalidNumber("x".to_owned())),
+            ("2-4,-1-3", 1, ParseErrorKind::InvalidNumber("".to_owned())),
+            (
+                "\n7-3,1-2",
+                2,
+                ParseErrorKind::Reversed { first: 7, last: 3 },
+            ),
+            (
+                "2-4,6-18446744073709551616",
+                1,
+                ParseErrorKind::Overflow("18446744073709551616".to_owned()),
+            ),
+        ];
+        for (input, line, kind) in cases {
+            assert_eq!(parse_error(input), ParseError { line, kind }, "{}", input);
+        }
+    }
+
+    #[test]
+    fn large_sections() {
+        let sections =
+            parse_sections(" 5000000000-18446744073709551615 , 0-5000000000 \n".as_bytes())
+                .unwrap();
+        assert_eq!(sections.count_overlaps(), 1);
+        assert_eq!(sections.duplicated_work(), 1);
+        assert_eq!(sections.0[0].left.len(), u64::MAX as u128 - 5000000000 + 1);
+    }
 }
@@ -0,0 +1,209 @@
+use crate::interval::Interval;
+use crate::{SectionPair, Sections};
+
+/// Suggested change to the assignments of a pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suggestion {
+    /// Neither range contains the other and the overlap is already as small as it can be
+    Keep,
+    /// New ranges with the same lengths as before
+    Move(SectionPair),
+    /// One elf has to clean every section of the camp, so it always contains the other one
+    Unfixable,
+}
+
+/// Nearest position to `target` within `from..=to`, with its distance
+fn nearest(target: i128, from: i128, to: i128) -> Option<(u128, i128)> {
+    (from <= to).then(|| {
+        let position = target.clamp(from, to);
+        (position.abs_diff(target), position)
+    })
+}
+
+/// Start of the `moving` range closest to where it is now, such that it overlaps `fixed` by at
+/// most `overlap` sections while staying within `lo..=hi`.
+fn shift(
+    fixed: Interval,
+    moving: Interval,
+    overlap: i128,
+    lo: i128,
+    hi: i128,
+) -> Option<(u128, i128)> {
+    let (start, len) = (fixed.first() as i128, fixed.len() as i128);
+    let (current, moving_len) = (moving.first() as i128, moving.len() as i128);
+    let after = nearest(current, start + len - overlap, hi - moving_len + 1);
+    let before = nearest(current, lo, start - moving_len + overlap);
+    after.into_iter().chain(before).min()
+}
+
+fn placed(start: i128, len: u128) -> Interval {
+    Interval::closed(start as u64, (start + len as i128 - 1) as u64).expect("Range is not empty")
+}
+
+impl SectionPair {
+    /// Suggests how to reassign the pair within `camp` so that neither range contains the other
+    /// and they overlap as little as possible, keeping the length of both ranges. Ranges are moved
+    /// by the fewest sections; one of them stays in place whenever that is enough.
+    pub fn rebalance(&self, camp: &Interval) -> Suggestion {
+        let (a, b) = (self.left.len(), self.right.len());
+        if a.max(b) >= camp.len() {
+            return Suggestion::Unfixable;
+        }
+        // Both ranges fit side by side unless they are together longer than the camp
+        let overlap = (a + b).saturating_sub(camp.len());
+        let within = camp.contains_interval(&self.left) && camp.contains_interval(&self.right);
+        if within && !self.contains() && self.overlap_len() == overlap {
+            return Suggestion::Keep;
+        }
+
+        let (lo, hi) = (camp.first() as i128, camp.last() as i128);
+        let overlap = overlap as i128;
+        let move_right = shift(self.left, self.right, overlap, lo, hi);
+        let move_left = shift(self.right, self.left, overlap, lo, hi);
+        let pair = match (move_right, move_left) {
+            (Some(right), left) if left.is_none_or(|left| right.0 <= left.0) => SectionPair {
+                left: self.left,
+                right: placed(right.1, b),
+            },
+            (_, Some(left)) => SectionPair {
+                left: placed(left.1, a),
+                right: self.right,
+            },
+            // Neither fits next to the other one, push them to the opposite ends of the camp
+            _ => {
+                let at_ends = |first_len, second_len| {
+                    (
+                        placed(lo, first_len),
+                        placed(hi - second_len as i128 + 1, second_len),
+                    )
+                };
+                let (left, right) = at_ends(a, b);
+                let (right_swapped, left_swapped) = at_ends(b, a);
+                let distance = |l: Interval, r: Interval| {
+                    l.first().abs_diff(self.left.first()) as u128
+                        + r.first().abs_diff(self.right.first()) as u128
+                };
+                if distance(left, right) <= distance(left_swapped, right_swapped) {
+                    SectionPair { left, right }
+                } else {
+                    SectionPair {
+                        left: left_swapped,
+                        right: right_swapped,
+                    }
+                }
+            }
+        };
+        Suggestion::Move(pair)
+    }
+}
+
+impl Sections {
+    /// Sections from the lowest to the highest assigned one
+    pub fn camp(&self) -> Option<Interval> {
+        let first = self.assignments().map(|(_, i)| i.first()).min()?;
+        let last = self.assignments().map(|(_, i)| i.last()).max()?;
+        Interval::closed(first, last)
+    }
+
+    /// Suggestions for every pair, keeping all ranges within the camp
+    pub fn rebalance(&self) -> Vec<Suggestion> {
+        let Some(camp) = self.camp() else {
+            return vec![];
+        };
+        self.0.iter().map(|p| p.rebalance(&camp)).collect()
+    }
+
+    /// Indices of the pairs which cannot be fixed
+    pub fn unfixable(&self) -> Vec<usize> {
+        self.rebalance()
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| **s == Suggestion::Unfixable)
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{load_data, parse_sections};
+
+    fn pair(s: &str) -> SectionPair {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn example() {
+        let sections = load_data("example.txt").unwrap();
+        assert_eq!(sections.camp(), Interval::closed(2, 9));
+        assert_eq!(
+            sections.rebalance(),
+            vec![
+                Suggestion::Keep,
+                Suggestion::Keep,
+                Suggestion::Move(pair("4-6,7-9")),
+                Suggestion::Move(pair("2-8,5-9")),
+                Suggestion::Move(pair("6-6,3-5")),
+                Suggestion::Move(pair("2-6,5-9")),
+            ]
+        );
+        assert_eq!(sections.unfixable(), vec![]);
+    }
+
+    #[test]
+    fn unfixable() {
+        let sections = parse_sections("1-9,3-4\n2-5,6-7\n4-6,4-6".as_bytes()).unwrap();
+        assert_eq!(sections.unfixable(), vec![0]);
+        assert_eq!(sections.rebalance()[2], Suggestion::Move(pair("4-6,1-3")));
+    }
+
+    #[test]
+    fn both_ranges_move() {
+        let camp = Interval::closed(1, 10).unwrap();
+        // Either range sticks out of the camp once the other one stays in place
+        assert_eq!(
+            pair("3-8,2-9").rebalance(&camp),
+            Suggestion::Move(pair("1-6,3-10"))
+        );
+    }
+
+    #[test]
+    fn input() {
+        let sections = load_data("test_1.txt").unwrap();
+        let camp = sections.camp().unwrap();
+        let mut moved = 0;
+        for (before, suggestion) in sections.0.iter().zip(sections.rebalance()) {
+            let spans_camp = before.left == camp || before.right == camp;
+            assert_eq!(suggestion == Suggestion::Unfixable, spans_camp);
+            let Suggestion::Move(after) = suggestion else {
+                continue;
+            };
+            moved += 1;
+            assert_eq!(before.left.len(), after.left.len());
+            assert_eq!(before.right.len(), after.right.len());
+            assert!(camp.contains_interval(&after.left) && camp.contains_interval(&after.right));
+            assert!(!after.contains());
+            let minimal = (after.left.len() + after.right.len()).saturating_sub(camp.len());
+            assert_eq!(after.overlap_len(), minimal);
+        }
+        let unfixable = sections.unfixable().len();
+        assert!(unfixable > 0);
+        assert!(moved + unfixable >= sections.count_intersections());
+    }
+
+    #[test]
+    fn full_range() {
+        let camp = Interval::closed(0, u64::MAX).unwrap();
+        assert_eq!(
+            pair("0-18446744073709551614,1-18446744073709551615").rebalance(&camp),
+            Suggestion::Keep
+        );
+        assert_eq!(
+            pair("5-5,0-18446744073709551614").rebalance(&camp),
+            Suggestion::Move(pair(
+                "18446744073709551615-18446744073709551615,0-18446744073709551614"
+            ))
+        );
+    }
+}
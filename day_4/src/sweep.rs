@@ -0,0 +1,196 @@
+use std::collections::BTreeSet;
+
+use crate::interval::Interval;
+use crate::Sections;
+
+/// Identifies an assignment by the index of its pair and the position of the elf within it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AssignmentId {
+    pub pair: usize,
+    pub elf: usize,
+}
+
+/// Sections covered by the largest number of elves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaxCoverage {
+    pub elves: usize,
+    pub ranges: Vec<Interval>,
+}
+
+impl Sections {
+    pub fn assignments(&self) -> impl Iterator<Item = (AssignmentId, Interval)> + '_ {
+        self.0.iter().enumerate().flat_map(|(pair, p)| {
+            [p.left, p.right]
+                .into_iter()
+                .enumerate()
+                .map(move |(elf, i)| (AssignmentId { pair, elf }, i))
+        })
+    }
+
+    /// Splits the covered sections into ranges of constant coverage. Returns `(start, end, elves)`
+    /// with exclusive ends, ordered by start. Uncovered ranges are included with 0 elves.
+    fn coverage(&self) -> Vec<(u128, u128, usize)> {
+        let mut events: Vec<(u128, i64)> = self
+            .assignments()
+            .flat_map(|(_, i)| [(i.first() as u128, 1), (i.end(), -1)])
+            .collect();
+        events.sort();
+
+        let mut segments = vec![];
+        let mut elves = 0i64;
+        for (i, (position, change)) in events.iter().enumerate() {
+            elves += change;
+            match events.get(i + 1) {
+                Some((next, _)) if next > position => {
+                    segments.push((*position, *next, elves as usize))
+                }
+                _ => (),
+            }
+        }
+        segments
+    }
+
+    /// Sections assigned to the most elves at once
+    pub fn max_coverage(&self) -> Option<MaxCoverage> {
+        let segments = self.coverage();
+        let elves = segments.iter().map(|s| s.2).max()?;
+        let mut ranges: Vec<Interval> = vec![];
+        for (start, end, _) in segments.into_iter().filter(|s| s.2 == elves) {
+            let range =
+                Interval::closed(start as u64, (end - 1) as u64).expect("Segment is not empty");
+            match ranges.last_mut() {
+                Some(last) if last.end() == start => {
+                    *last = last.union(&range).expect("Ranges touch")
+                }
+                _ => ranges.push(range),
+            }
+        }
+        Some(MaxCoverage { elves, ranges })
+    }
+
+    /// Sections nobody is assigned to, between the lowest and the highest assigned section
+    pub fn gaps(&self) -> Vec<Interval> {
+        self.coverage()
+            .into_iter()
+            .filter(|s| s.2 == 0)
+            .map(|(start, end, _)| {
+                Interval::closed(start as u64, (end - 1) as u64).expect("Segment is not empty")
+            })
+            .collect()
+    }
+
+    /// Every two assignments from any pairs which share at least one section.
+    ///
+    /// Assignments are swept by their first section while the ones still reaching the current
+    /// section are kept in a set, so it runs in `O(n log n + k)` for `k` overlapping pairs.
+    pub fn overlapping_assignments(&self) -> Vec<(AssignmentId, AssignmentId)> {
+        let mut assignments: Vec<_> = self.assignments().collect();
+        assignments.sort_by_key(|(id, i)| (i.first(), *id));
+
+        let mut active: BTreeSet<(u64, AssignmentId)> = BTreeSet::new();
+        let mut overlapping = vec![];
+        for (id, interval) in assignments {
+            while let Some((last, _)) = active.first() {
+                if *last >= interval.first() {
+                    break;
+                }
+                active.pop_first();
+            }
+            overlapping.extend(
+                active
+                    .iter()
+                    .map(|(_, other)| ((*other).min(id), (*other).max(id))),
+            );
+            active.insert((interval.last(), id));
+        }
+        overlapping.sort();
+        overlapping
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::load_data;
+
+    fn interval(first: u64, last: u64) -> Interval {
+        Interval::closed(first, last).unwrap()
+    }
+
+    #[test]
+    fn example() {
+        let sections = load_data("example.txt").unwrap();
+        assert_eq!(
+            sections.max_coverage(),
+            Some(MaxCoverage {
+                elves: 8,
+                ranges: vec![interval(6, 6)]
+            })
+        );
+        assert_eq!(sections.gaps(), vec![]);
+
+        let overlapping = sections.overlapping_assignments();
+        let brute_force = brute_force_overlaps(&sections);
+        assert_eq!(overlapping, brute_force);
+    }
+
+    #[test]
+    fn gaps_and_plateaus() {
+        let sections: Sections = [((1, 3), (10, 12)), ((2, 4), (11, 20))]
+            .into_iter()
+            .map(|(l, r)| crate::SectionPair {
+                left: interval(l.0, l.1),
+                right: interval(r.0, r.1),
+            })
+            .collect();
+        assert_eq!(sections.gaps(), vec![interval(5, 9)]);
+        assert_eq!(
+            sections.max_coverage().unwrap().ranges,
+            vec![interval(2, 3), interval(11, 12)]
+        );
+        assert_eq!(Sections(vec![]).max_coverage(), None);
+    }
+
+    fn brute_force_overlaps(sections: &Sections) -> Vec<(AssignmentId, AssignmentId)> {
+        let assignments: Vec<_> = sections.assignments().collect();
+        let mut overlapping = vec![];
+        for (i, (a, a_interval)) in assignments.iter().enumerate() {
+            for (b, b_interval) in &assignments[i + 1..] {
+                if a_interval.overlaps(b_interval) {
+                    overlapping.push((*a.min(b), *a.max(b)));
+                }
+            }
+        }
+        overlapping.sort();
+        overlapping
+    }
+
+    #[test]
+    fn input_matches_brute_force() {
+        let sections = load_data("test_1.txt").unwrap();
+        assert_eq!(
+            sections.overlapping_assignments(),
+            brute_force_overlaps(&sections)
+        );
+        let within_pairs = sections
+            .overlapping_assignments()
+            .iter()
+            .filter(|(a, b)| a.pair == b.pair)
+            .count();
+        assert_eq!(within_pairs, 779);
+    }
+
+    #[test]
+    fn full_range() {
+        let sections: Sections = [crate::SectionPair {
+            left: interval(0, u64::MAX),
+            right: interval(u64::MAX, u64::MAX),
+        }]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            sections.max_coverage().unwrap().ranges,
+            vec![interval(u64::MAX, u64::MAX)]
+        );
+    }
+}
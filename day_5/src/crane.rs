@@ -0,0 +1,150 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::Move;
+
+/// Decides how crates lifted by a move land on the target stack.
+pub trait Crane: fmt::Debug {
+    /// Takes crates lifted from the source stack and returns them in the order they are put on
+    /// the target stack. Both are listed bottom crate first.
+    fn arrange(&self, lifted: &[char]) -> Vec<char>;
+}
+
+/// Moves crates one at a time, reversing their order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrateMover9000;
+
+/// Moves all crates at once, keeping their order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrateMover9001;
+
+/// Lifts at most `capacity` crates at once, keeping their order within a batch. The top batch is
+/// moved first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitedCapacity {
+    capacity: usize,
+}
+
+/// Moves batches like `LimitedCapacity`, but flips every other batch, starting with the second
+/// one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlternatingBatches {
+    capacity: usize,
+}
+
+impl Crane for CrateMover9000 {
+    fn arrange(&self, lifted: &[char]) -> Vec<char> {
+        lifted.iter().rev().copied().collect()
+    }
+}
+
+impl Crane for CrateMover9001 {
+    fn arrange(&self, lifted: &[char]) -> Vec<char> {
+        lifted.to_vec()
+    }
+}
+
+/// Splits `lifted` into batches of `capacity` counted from the top and stacks them top batch
+/// first, reversing the ones for which `flip` returns true.
+fn in_batches(lifted: &[char], capacity: usize, flip: impl Fn(usize) -> bool) -> Vec<char> {
+    let mut placed = Vec::with_capacity(lifted.len());
+    for (i, batch) in lifted.rchunks(capacity).enumerate() {
+        if flip(i) {
+            placed.extend(batch.iter().rev());
+        } else {
+            placed.extend(batch);
+        }
+    }
+    placed
+}
+
+impl LimitedCapacity {
+    /// Returns `None` for a crane which can't lift anything
+    pub fn new(capacity: usize) -> Option<Self> {
+        (capacity > 0).then_some(Self { capacity })
+    }
+}
+
+impl Crane for LimitedCapacity {
+    fn arrange(&self, lifted: &[char]) -> Vec<char> {
+        in_batches(lifted, self.capacity, |_| false)
+    }
+}
+
+impl AlternatingBatches {
+    /// Returns `None` for a crane which can't lift anything
+    pub fn new(capacity: usize) -> Option<Self> {
+        (capacity > 0).then_some(Self { capacity })
+    }
+}
+
+impl Crane for AlternatingBatches {
+    fn arrange(&self, lifted: &[char]) -> Vec<char> {
+        in_batches(lifted, self.capacity, |i| i % 2 == 1)
+    }
+}
+
+/// Applies the move to the stacks. Returns the lifted crates, bottom one first, or the number of
+/// crates available if the source stack is too short, in which case stacks are left untouched.
+pub(crate) fn apply(
+    stacks: &mut [VecDeque<char>],
+    m: &Move,
+    crane: &dyn Crane,
+) -> Result<Vec<char>, usize> {
+    let from = &mut stacks[m.from as usize];
+    let count = m.count as usize;
+    if from.len() < count {
+        return Err(from.len());
+    }
+    let lifted: Vec<char> = from.drain(from.len() - count..).collect();
+    stacks[m.to as usize].extend(crane.arrange(&lifted));
+    Ok(lifted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Storage;
+
+    fn arranged(crane: &dyn Crane) -> String {
+        let lifted: Vec<char> = "abcdefg".chars().collect();
+        crane.arrange(&lifted).into_iter().collect()
+    }
+
+    #[test]
+    fn arrangements() {
+        assert_eq!(arranged(&CrateMover9000), "gfedcba");
+        assert_eq!(arranged(&CrateMover9001), "abcdefg");
+        assert_eq!(arranged(&LimitedCapacity::new(3).unwrap()), "efgbcda");
+        assert_eq!(arranged(&AlternatingBatches::new(3).unwrap()), "efgdcba");
+        assert_eq!(arranged(&AlternatingBatches::new(2).unwrap()), "fgedbca");
+        assert_eq!(LimitedCapacity::new(0), None);
+        assert_eq!(AlternatingBatches::new(0), None);
+    }
+
+    fn tops(crane: &dyn Crane) -> String {
+        let mut storage = Storage::new("first.txt").unwrap();
+        storage.move_crates(crane).unwrap();
+        storage.top_of_stacks()
+    }
+
+    #[test]
+    fn limited_capacity_bounds() {
+        let one = LimitedCapacity::new(1).unwrap();
+        assert_eq!(tops(&one), tops(&CrateMover9000));
+        let unlimited = LimitedCapacity::new(usize::MAX).unwrap();
+        assert_eq!(tops(&unlimited), tops(&CrateMover9001));
+        // Neither reversing each crate nor keeping the whole move
+        assert_eq!(tops(&LimitedCapacity::new(3).unwrap()), "LGMFQPHMM");
+    }
+
+    #[test]
+    fn alternating_batches() {
+        // Single crate batches land in the same order regardless of flipping
+        let one = AlternatingBatches::new(1).unwrap();
+        assert_eq!(tops(&one), tops(&CrateMover9000));
+        let unlimited = AlternatingBatches::new(usize::MAX).unwrap();
+        assert_eq!(tops(&unlimited), tops(&CrateMover9001));
+        assert_eq!(tops(&AlternatingBatches::new(2).unwrap()), "BNTBMDCLV");
+    }
+}
@@ -1,87 +1,270 @@
+pub mod crane;
+pub mod render;
+pub mod simulation;
+pub mod validation;
+
 use std::collections::VecDeque;
+use std::fmt;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
 use anyhow::Result;
+use crane::{Crane, CrateMover9000, CrateMover9001};
 use itertools::Itertools;
+use simulation::MoveError;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Move {
     pub count: u32,
+    /// Index of the stack counted from 0
     pub from: u32,
+    /// Index of the stack counted from 0
     pub to: u32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Storage {
     pub stacks: Vec<VecDeque<char>>,
     pub moves: Vec<Move>,
 }
 
-impl Storage {
-    pub fn new(file: &str) -> Result<Self> {
-        let file = File::open(file)?;
-        let reader = BufReader::new(file);
-        let lines = reader
-            .lines()
-            .filter_map(|l| l.ok())
-            .collect::<Vec<String>>();
-        let no_stacks = lines[0].len() / 4 + 1;
-        let mut storage = Self {
-            stacks: vec![VecDeque::new(); no_stacks],
-            moves: vec![],
+/// Storage as read from the input, remembering where each move was written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedStorage {
+    storage: Storage,
+    /// Line of each move counted from 1
+    move_lines: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// Drawing isn't followed by a row of stack labels
+    MissingLabels,
+    /// Stack labels have to count from 1 without gaps
+    UnexpectedLabel {
+        expected: usize,
+        found: String,
+    },
+    /// Crate slot is neither `[X]` nor blank
+    MalformedCrate(String),
+    /// Crate drawn right of the last labelled stack
+    UnlabelledStack(usize),
+    /// Crate drawn above an empty slot
+    FloatingCrate {
+        stack: usize,
+    },
+    /// Line isn't written as `move N from A to B`
+    MalformedMove(String),
+    InvalidNumber(String),
+    /// Move refers to a stack label which doesn't exist
+    UnknownStack {
+        label: u32,
+        stacks: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line number counted from 1
+    pub line: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingLabels => write!(f, "missing row of stack labels"),
+            Self::UnexpectedLabel { expected, found } => {
+                write!(f, "expected stack label {}, got {:?}", expected, found)
+            }
+            Self::MalformedCrate(c) => write!(f, "expected crate as [X], got {:?}", c),
+            Self::UnlabelledStack(stack) => write!(f, "crate in unlabelled stack {}", stack),
+            Self::FloatingCrate { stack } => write!(f, "crate floats above stack {}", stack),
+            Self::MalformedMove(m) => {
+                write!(f, "expected move as `move N from A to B`, got {:?}", m)
+            }
+            Self::InvalidNumber(n) => write!(f, "invalid number {:?}", n),
+            Self::UnknownStack { label, stacks } => {
+                write!(f, "stack {} doesn't exist, there are {}", label, stacks)
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Reads the row of stack labels `1 2 .. n`. Returns `None` if the line isn't made of numbers.
+fn parse_labels(line: &str) -> Option<Result<usize, ParseErrorKind>> {
+    let labels: Vec<&str> = line.split_whitespace().collect();
+    if labels.is_empty() || labels.iter().any(|l| l.parse::<u32>().is_err()) {
+        return None;
+    }
+    let unexpected = labels
+        .iter()
+        .enumerate()
+        .find(|(i, l)| l.parse::<usize>() != Ok(i + 1));
+    Some(match unexpected {
+        Some((i, found)) => Err(ParseErrorKind::UnexpectedLabel {
+            expected: i + 1,
+            found: found.to_string(),
+        }),
+        None => Ok(labels.len()),
+    })
+}
+
+/// Reads one row of the drawing. Slots are 4 characters wide, `None` stands for a blank one.
+fn parse_crates(line: &str, stacks: usize) -> Result<Vec<Option<char>>, ParseErrorKind> {
+    let chars: Vec<char> = line.trim_end().chars().collect();
+    let mut row = vec![None; stacks];
+    for (stack, slot) in chars.chunks(4).enumerate() {
+        let crate_ = match slot {
+            [' ', ' ', ' ', ' '] | [' ', ' ', ' '] => None,
+            ['[', c, ']', ' '] | ['[', c, ']'] if !c.is_whitespace() => Some(*c),
+            _ => {
+                let slot: String = slot.iter().collect();
+                return Err(ParseErrorKind::MalformedCrate(slot.trim_end().to_owned()));
+            }
         };
+        match (crate_, row.get_mut(stack)) {
+            (None, _) => (),
+            (Some(c), Some(slot)) => *slot = Some(c),
+            (Some(_), None) => return Err(ParseErrorKind::UnlabelledStack(stack + 1)),
+        }
+    }
+    Ok(row)
+}
+
+fn parse_number(s: &str) -> Result<u32, ParseErrorKind> {
+    s.parse()
+        .map_err(|_| ParseErrorKind::InvalidNumber(s.to_owned()))
+}
+
+/// Builds stacks from rows of the drawing given with their line numbers, top row first.
+fn stack_drawing(
+    drawing: &[(usize, String)],
+    stacks: usize,
+) -> Result<Vec<VecDeque<char>>, ParseError> {
+    let mut columns = vec![VecDeque::new(); stacks];
+    // Bottom row goes first so that the stacks grow upwards
+    for (height, (line, row)) in drawing.iter().rev().enumerate() {
+        let error = |kind| ParseError { line: *line, kind };
+        let row = parse_crates(row, stacks).map_err(error)?;
+        for (stack, (c, crates)) in row.into_iter().zip(&mut columns).enumerate() {
+            let Some(c) = c else { continue };
+            if crates.len() < height {
+                return Err(error(ParseErrorKind::FloatingCrate { stack: stack + 1 }));
+            }
+            crates.push_back(c);
+        }
+    }
+    Ok(columns)
+}
 
-        lines.iter().filter(|l| l.contains('[')).for_each(|l| {
-            l.chars()
-                .skip(1)
-                .step_by(4)
-                .zip(storage.stacks.iter_mut())
-                .filter(|(letter, _)| letter != &' ')
-                .for_each(|(letter, stack)| {
-                    stack.push_front(letter);
-                })
-        });
+/// Parses `move N from A to B` with stack labels counted from 1.
+fn parse_move(line: &str, stacks: usize) -> Result<Move, ParseErrorKind> {
+    let Some(("move", count, "from", from, "to", to)) = line.split_whitespace().collect_tuple()
+    else {
+        return Err(ParseErrorKind::MalformedMove(line.trim().to_owned()));
+    };
+    let index = |label: &str| {
+        let label = parse_number(label)?;
+        match label.checked_sub(1) {
+            Some(index) if (index as usize) < stacks => Ok(index),
+            _ => Err(ParseErrorKind::UnknownStack { label, stacks }),
+        }
+    };
+    Ok(Move {
+        count: parse_number(count)?,
+        from: index(from)?,
+        to: index(to)?,
+    })
+}
 
-        // TODO: Rewrite it so that for_each will become map forwarding parse error in case of
-        // failure
-        lines.iter().filter(|l| l.contains('m')).for_each(|l| {
-            let Some((count, from, to)) = l.split(' ').skip(1).step_by(2).map(|s| s.parse::<u32>()).filter_map(|s| s.ok()).collect_tuple() else {
-                panic!("Unexpected format of move commands: {}", l);
-            };
+impl Storage {
+    pub fn new(file: &str) -> Result<Self> {
+        Ok(Self::load_with_lines(file)?.into_storage())
+    }
 
-            // Subtract one from 'from' and 'to' to match index of Vecs
-            storage.moves.push(Move { count, from: from -1 , to : to -1 })
-        });
+    /// Like `Storage::new`, also keeping the line of each move
+    pub fn load_with_lines(file: &str) -> Result<ParsedStorage> {
+        let file = File::open(file)?;
+        Self::parse_with_lines(BufReader::new(file))
+    }
 
-        Ok(storage)
+    /// Parses the drawing of stacks followed by the moves. The number of stacks is given by the
+    /// row of labels below the drawing.
+    pub fn parse(reader: impl BufRead) -> Result<Self> {
+        Ok(Self::parse_with_lines(reader)?.into_storage())
     }
 
-    pub fn move_crates_9000(&mut self) {
-        let Self { stacks, moves } = self;
+    /// Like `Storage::parse`, also keeping the line of each move
+    pub fn parse_with_lines(reader: impl BufRead) -> Result<ParsedStorage> {
+        let mut drawing = vec![];
+        let mut stacks: Option<Vec<VecDeque<char>>> = None;
+        let mut moves = vec![];
+        let mut move_lines = vec![];
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let error = |kind| ParseError { line: i + 1, kind };
+            if line.trim().is_empty() {
+                continue;
+            }
+            match (&stacks, parse_labels(&line)) {
+                (None, Some(labels)) => {
+                    stacks = Some(stack_drawing(&drawing, labels.map_err(error)?)?);
+                }
+                (None, None) => drawing.push((i + 1, line)),
+                (Some(stacks), _) => {
+                    moves.push(parse_move(&line, stacks.len()).map_err(error)?);
+                    move_lines.push(i + 1);
+                }
+            }
+        }
 
-        moves.iter().for_each(|m| {
-            let Move { count, from, to } = m;
-            for _ in 0..*count {
-                let Some(poped) = stacks[*from as usize].pop_back() else {
-                    panic!("{:#?} would cause move from empty stack", m);
-                };
-                stacks[*to as usize].push_back(poped);
+        let Some(stacks) = stacks else {
+            let line = drawing.last().map_or(1, |(line, _)| line + 1);
+            return Err(ParseError {
+                line,
+                kind: ParseErrorKind::MissingLabels,
             }
-        });
+            .into());
+        };
+        Ok(ParsedStorage {
+            storage: Self { stacks, moves },
+            move_lines,
+        })
     }
 
-    pub fn move_crates_9001(&mut self) {
+    /// Runs all moves with `crane`. Stops at the first move taking more crates than its source
+    /// stack holds, leaving the earlier moves applied.
+    pub fn move_crates(&mut self, crane: &dyn Crane) -> Result<(), MoveError> {
         let Self { stacks, moves } = self;
+        for (step, m) in moves.iter().enumerate() {
+            crane::apply(stacks, m, crane).map_err(|available| MoveError {
+                step,
+                failed: *m,
+                available,
+            })?;
+        }
+        Ok(())
+    }
 
-        moves.iter().for_each(|m| {
-            let Move { count, from, to } = m;
-            let split_index = stacks[*from as usize].len() - *count as usize;
-            for _ in 0..*count {
-                let mut moved_crates = stacks[*from as usize].split_off(split_index);
-                stacks[*to as usize].append(&mut moved_crates);
-            }
-        });
+    pub fn move_crates_9000(&mut self) {
+        if let Err(e) = self.move_crates(&CrateMover9000) {
+            panic!("{}", e);
+        }
+    }
+
+    pub fn move_crates_9001(&mut self) {
+        if let Err(e) = self.move_crates(&CrateMover9001) {
+            panic!("{}", e);
+        }
     }
 
     pub fn top_of_stacks(&self) -> String {
@@ -92,6 +275,21 @@ impl Storage {
     }
 }
 
+impl ParsedStorage {
+    pub fn storage(&self) -> &Storage {
+        &self.storage
+    }
+
+    pub fn into_storage(self) -> Storage {
+        self.storage
+    }
+
+    /// Line of the move with index `step`, counted from 1
+    pub fn move_line(&self, step: usize) -> Option<usize> {
+        self.move_lines.get(step).copied()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,4 +321,88 @@ mod tests {
         storage.move_crates_9001();
         assert_eq!("GMPMLWNMG".to_owned(), storage.top_of_stacks());
     }
+
+    fn parse_error(input: &str) -> ParseError {
+        let err = Storage::parse(input.as_bytes()).unwrap_err();
+        err.downcast::<ParseError>().unwrap()
+    }
+
+    #[test]
+    fn many_stacks() {
+        let labels: String = (1..=11).map(|l| format!(" {:<3}", l)).collect();
+        let input = format!(
+            "{}[K]\n[A]{}[J] [L]\n{}\nmove 1 from 10 to 2\nmove 2 from 11 to 1",
+            " ".repeat(40),
+            " ".repeat(33),
+            labels
+        );
+        let mut storage = Storage::parse(input.as_bytes()).unwrap();
+        assert_eq!(storage.stacks.len(), 11);
+        assert_eq!(storage.stacks[10], ['L', 'K']);
+        assert_eq!(
+            storage.moves,
+            vec![
+                Move {
+                    count: 1,
+                    from: 9,
+                    to: 1
+                },
+                Move {
+                    count: 2,
+                    from: 10,
+                    to: 0
+                }
+            ]
+        );
+        storage.move_crates_9000();
+        assert_eq!(storage.top_of_stacks(), "LJ---------");
+    }
+
+    #[test]
+    fn invalid_input() {
+        use ParseErrorKind::*;
+        let cases = [
+            (
+                "[A]\n 1\n\nmove 1 from 2 to 1",
+                4,
+                UnknownStack {
+                    label: 2,
+                    stacks: 1,
+                },
+            ),
+            (
+                "[A]\n 1 2\nmove 1 from 0 to 2",
+                3,
+                UnknownStack {
+                    label: 0,
+                    stacks: 2,
+                },
+            ),
+            (
+                "[A]\n 1 3",
+                2,
+                UnexpectedLabel {
+                    expected: 2,
+                    found: "3".to_owned(),
+                },
+            ),
+            ("[A]\n", 2, MissingLabels),
+            ("[A] (B)\n 1 2", 1, MalformedCrate("(B)".to_owned())),
+            ("    [B]\n[A]    \n 1   2", 1, FloatingCrate { stack: 2 }),
+            ("[A] [B]\n 1", 1, UnlabelledStack(2)),
+            (
+                "[A]\n 1\nmove one from 1 to 1",
+                3,
+                InvalidNumber("one".to_owned()),
+            ),
+            (
+                "[A]\n 1\nmove 1 to 1",
+                3,
+                MalformedMove("move 1 to 1".to_owned()),
+            ),
+        ];
+        for (input, line, kind) in cases {
+            assert_eq!(parse_error(input), ParseError { line, kind }, "{}", input);
+        }
+    }
 }
@@ -0,0 +1,126 @@
+use std::fmt;
+use std::fs;
+
+use anyhow::Result;
+use itertools::Itertools;
+
+use crate::{Move, Storage};
+
+impl fmt::Display for Move {
+    /// Writes the move the way it's written in the puzzle input, with stack labels counted from 1
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.count,
+            self.from + 1,
+            self.to + 1
+        )
+    }
+}
+
+impl fmt::Display for Storage {
+    /// Draws the stacks with their labels followed by the moves, in the format read by
+    /// `Storage::parse`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let height = self.stacks.iter().map(|s| s.len()).max().unwrap_or(0);
+        for level in (0..height).rev() {
+            let row = self.stacks.iter().map(|stack| match stack.get(level) {
+                Some(c) => format!("[{}]", c),
+                None => "   ".to_owned(),
+            });
+            writeln!(f, "{}", row.format(" "))?;
+        }
+        // Labels fill whole 4 character slots so that they stay aligned with the crates
+        let labels: String = (1..=self.stacks.len())
+            .map(|label| format!(" {:<3}", label))
+            .collect();
+        let width = (4 * self.stacks.len()).saturating_sub(1);
+        writeln!(f, "{:<width$}", labels.trim_end(), width = width)?;
+
+        if !self.moves.is_empty() {
+            writeln!(f)?;
+        }
+        for m in &self.moves {
+            writeln!(f, "{}", m)?;
+        }
+        Ok(())
+    }
+}
+
+impl Storage {
+    /// Saves the current stacks and moves so that `Storage::new` reads them back
+    pub fn save(&self, file: &str) -> Result<()> {
+        fs::write(file, self.to_string())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_unchanged() {
+        let storage = Storage::new("example.txt").unwrap();
+        let file = fs::read_to_string("example.txt").unwrap();
+        assert_eq!(storage.to_string(), file);
+    }
+
+    #[test]
+    fn mid_simulation() {
+        let mut storage = Storage::new("first.txt").unwrap();
+        let remaining = storage.moves.split_off(200);
+        storage.move_crates_9001();
+        storage.moves = remaining;
+
+        let rendered = storage.to_string();
+        let parsed = Storage::parse(rendered.as_bytes()).unwrap();
+        assert_eq!(parsed, storage);
+
+        // Named after the process so that concurrent runs don't share the file
+        let name = format!("day_5_mid_simulation_{}.txt", std::process::id());
+        let file = std::env::temp_dir().join(name);
+        let file = file.to_str().unwrap();
+        storage.save(file).unwrap();
+        let mut loaded = Storage::new(file).unwrap();
+        fs::remove_file(file).unwrap();
+        loaded.move_crates_9001();
+        assert_eq!(loaded.top_of_stacks(), "GMPMLWNMG");
+    }
+
+    #[test]
+    fn many_stacks() {
+        let mut storage = Storage::parse("[A]\n 1\n".as_bytes()).unwrap();
+        storage.stacks.resize(12, Default::default());
+        storage.stacks[10].extend(['X', 'Y']);
+        storage.moves.push(Move {
+            count: 2,
+            from: 10,
+            to: 11,
+        });
+        let rendered = storage.to_string();
+        assert!(rendered.contains(" 10  11  12\n\nmove 2 from 11 to 12\n"));
+        assert_eq!(Storage::parse(rendered.as_bytes()).unwrap(), storage);
+    }
+
+    #[test]
+    fn labels_aligned() {
+        let mut storage = Storage::parse("[A]\n 1\n".as_bytes()).unwrap();
+        storage.stacks.resize(120, Default::default());
+        storage.stacks[104].push_back('X');
+        let rendered = storage.to_string();
+        let (crates, labels) = rendered.lines().collect_tuple().unwrap();
+        assert_eq!(&crates[4 * 104 + 1..4 * 104 + 2], "X");
+        assert_eq!(&labels[4 * 104 + 1..4 * 104 + 4], "105");
+        assert_eq!(&labels[4 * 119 + 1..], "120");
+        assert_eq!(Storage::parse(rendered.as_bytes()).unwrap(), storage);
+    }
+
+    #[test]
+    fn empty_stacks() {
+        let storage = Storage::parse(" 1   2 \n".as_bytes()).unwrap();
+        assert!(storage.stacks.iter().all(|s| s.is_empty()));
+        assert_eq!(storage.to_string(), " 1   2 \n");
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::rc::Rc;
+
+use crate::crane::{self, Crane};
+use crate::{Move, Storage};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveError {
+    /// Index of the failing move counted from 0
+    pub step: usize,
+    pub failed: Move,
+    /// Crates on the source stack when the move was attempted
+    pub available: usize,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "step {} ({}) needs {} crates, only {} available",
+            self.step + 1,
+            self.failed,
+            self.failed.count,
+            self.available
+        )
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// Applies moves one at a time, remembering the crates each one lifted so that it can be undone.
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    stacks: Vec<VecDeque<char>>,
+    moves: Vec<Move>,
+    crane: Rc<dyn Crane>,
+    /// Cranes replacing the default one for single moves, by index of the move
+    overrides: HashMap<usize, Rc<dyn Crane>>,
+    /// Crates lifted by each applied move, bottom one first
+    history: Vec<Vec<char>>,
+    /// Number of undone moves which can be redone
+    undone: usize,
+}
+
+impl Simulation {
+    /// Simulation running every move with `crane`
+    pub fn new(storage: &Storage, crane: impl Crane + 'static) -> Self {
+        Self {
+            stacks: storage.stacks.clone(),
+            moves: storage.moves.clone(),
+            crane: Rc::new(crane),
+            overrides: HashMap::new(),
+            history: vec![],
+            undone: 0,
+        }
+    }
+
+    /// Runs the move with index `step` with another crane
+    pub fn set_crane(&mut self, step: usize, crane: impl Crane + 'static) {
+        self.overrides.insert(step, Rc::new(crane));
+    }
+
+    /// Stacks after the applied moves
+    pub fn stacks(&self) -> &[VecDeque<char>] {
+        &self.stacks
+    }
+
+    /// Number of applied moves
+    pub fn position(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position() == self.moves.len()
+    }
+
+    /// Current state with the moves left to apply
+    pub fn storage(&self) -> Storage {
+        Storage {
+            stacks: self.stacks.clone(),
+            moves: self.moves[self.position()..].to_vec(),
+        }
+    }
+
+    /// Applies the next move. Returns `false` if there are no moves left. A failing move leaves
+    /// the stacks untouched.
+    pub fn step(&mut self) -> Result<bool, MoveError> {
+        let step = self.position();
+        let Some(&m) = self.moves.get(step) else {
+            return Ok(false);
+        };
+        let crane = Rc::clone(self.overrides.get(&step).unwrap_or(&self.crane));
+        let lifted =
+            crane::apply(&mut self.stacks, &m, crane.as_ref()).map_err(|available| MoveError {
+                step,
+                failed: m,
+                available,
+            })?;
+        self.history.push(lifted);
+        self.undone = self.undone.saturating_sub(1);
+        Ok(true)
+    }
+
+    /// Reverts the last applied move. Returns `false` if no move was applied.
+    pub fn undo(&mut self) -> bool {
+        let Some(lifted) = self.history.pop() else {
+            return false;
+        };
+        let m = self.moves[self.position()];
+        let to = &mut self.stacks[m.to as usize];
+        to.truncate(to.len() - lifted.len());
+        self.stacks[m.from as usize].extend(lifted);
+        self.undone += 1;
+        true
+    }
+
+    /// Applies again the last undone move. Returns `false` if there is nothing to redo.
+    pub fn redo(&mut self) -> Result<bool, MoveError> {
+        if self.undone == 0 {
+            return Ok(false);
+        }
+        self.step()
+    }
+
+    /// Applies the remaining moves, stopping at the first failing one
+    pub fn run(&mut self) -> Result<(), MoveError> {
+        while self.step()? {}
+        Ok(())
+    }
+
+    /// Moves back or forth so that exactly `position` moves are applied
+    pub fn seek(&mut self, position: usize) -> Result<(), MoveError> {
+        while self.position() > position {
+            self.undo();
+        }
+        while self.position() < position.min(self.moves.len()) {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// State after the first `step` moves, leaving the simulation as it is
+    pub fn state_after(&self, step: usize) -> Result<Storage, MoveError> {
+        let mut simulation = self.clone();
+        simulation.seek(step)?;
+        Ok(simulation.storage())
+    }
+
+    /// First move of the whole procedure which fails, leaving the simulation as it is
+    pub fn first_failure(&self) -> Option<MoveError> {
+        let mut simulation = self.clone();
+        simulation.seek(0).and_then(|_| simulation.run()).err()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crane::{AlternatingBatches, CrateMover9000, CrateMover9001};
+
+    #[test]
+    fn example() {
+        let storage = Storage::new("example.txt").unwrap();
+        let mut simulation = Simulation::new(&storage, CrateMover9000);
+        assert!(simulation.step().unwrap());
+        assert_eq!(simulation.storage().top_of_stacks(), "DCP");
+        simulation.run().unwrap();
+        assert!(simulation.is_finished());
+        assert_eq!(simulation.storage().top_of_stacks(), "CMZ");
+        assert!(!simulation.step().unwrap());
+
+        assert!(simulation.undo());
+        assert_eq!(simulation.position(), 3);
+        assert_eq!(simulation.storage().moves, storage.moves[3..]);
+        assert!(simulation.redo().unwrap());
+        assert!(!simulation.redo().unwrap());
+        assert_eq!(simulation.storage().top_of_stacks(), "CMZ");
+
+        assert_eq!(simulation.state_after(1).unwrap().top_of_stacks(), "DCP");
+        assert_eq!(simulation.position(), 4);
+        simulation.seek(0).unwrap();
+        assert_eq!(simulation.storage(), storage);
+        assert!(!simulation.undo());
+    }
+
+    fn check_against_storage(crane: impl Crane + 'static, expected: &str) {
+        let storage = Storage::new("first.txt").unwrap();
+        let mut simulation = Simulation::new(&storage, crane);
+        simulation.run().unwrap();
+        assert_eq!(simulation.storage().top_of_stacks(), expected);
+
+        // Every state is restored exactly when walking back
+        let mut states = vec![];
+        simulation.seek(0).unwrap();
+        while !simulation.is_finished() {
+            states.push(simulation.stacks().to_vec());
+            simulation.step().unwrap();
+        }
+        for state in states.iter().rev() {
+            simulation.undo();
+            assert_eq!(simulation.stacks(), state);
+        }
+    }
+
+    #[test]
+    fn matches_storage_methods() {
+        check_against_storage(CrateMover9000, "WHTLRMZRC");
+        check_against_storage(CrateMover9001, "GMPMLWNMG");
+        check_against_storage(AlternatingBatches::new(2).unwrap(), "BNTBMDCLV");
+    }
+
+    #[test]
+    fn crane_per_move() {
+        let storage = Storage::new("example.txt").unwrap();
+        let mut simulation = Simulation::new(&storage, CrateMover9000);
+        simulation.set_crane(1, CrateMover9001);
+        simulation.run().unwrap();
+        assert_eq!(simulation.storage().top_of_stacks(), "CMD");
+
+        // Redone moves use the same cranes
+        simulation.seek(1).unwrap();
+        while simulation.redo().unwrap() {}
+        assert_eq!(simulation.storage().top_of_stacks(), "CMD");
+    }
+
+    #[test]
+    fn failing_move() {
+        let input =
+            "[A]\n[B] [C]\n 1   2\n\nmove 1 from 2 to 1\nmove 3 from 1 to 2\nmove 4 from 2 to 1";
+        let storage = Storage::parse(input.as_bytes()).unwrap();
+        let mut simulation = Simulation::new(&storage, CrateMover9001);
+        let expected = MoveError {
+            step: 2,
+            failed: storage.moves[2],
+            available: 3,
+        };
+        assert_eq!(simulation.first_failure(), Some(expected.clone()));
+        assert_eq!(simulation.position(), 0);
+
+        assert_eq!(simulation.run(), Err(expected));
+        assert_eq!(simulation.position(), 2);
+        assert_eq!(simulation.stacks()[0], []);
+        assert_eq!(simulation.stacks()[1], ['B', 'A', 'C']);
+        assert!(simulation.undo());
+        assert_eq!(simulation.stacks()[0], ['B', 'A', 'C']);
+    }
+}
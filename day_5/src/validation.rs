@@ -0,0 +1,192 @@
+use crate::{Move, ParsedStorage, Storage};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Problem {
+    /// Number of crates the source stack lacks
+    MissingCrates(u64),
+    /// Index of a stack, counted from 0, which doesn't exist
+    UnknownStack(u32),
+}
+
+/// Move which can't be carried out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidMove {
+    /// Index of the move counted from 0
+    pub step: usize,
+    /// Line of the move counted from 1, known when validating a `ParsedStorage`
+    pub line: Option<usize>,
+    pub failed: Move,
+    pub problem: Problem,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Validation {
+    pub invalid: Vec<InvalidMove>,
+    /// Fewest crates each stack has to start with for every move to succeed
+    pub required_heights: Vec<u64>,
+}
+
+impl Validation {
+    pub fn is_valid(&self) -> bool {
+        self.invalid.is_empty()
+    }
+}
+
+impl Storage {
+    /// Checks the moves tracking only the heights of the stacks, which don't depend on the crane
+    /// model. Stacks are left untouched.
+    ///
+    /// An invalid move is assumed to take the missing crates anyway, so that every move which
+    /// would still fail after fixing the earlier ones gets reported. A move referring to a stack
+    /// which doesn't exist is reported and skipped. Lines of the moves aren't
+    /// known here, `ParsedStorage::validate` reports them.
+    pub fn validate(&self) -> Validation {
+        self.validate_with_lines(|_| None)
+    }
+
+    fn validate_with_lines(&self, line: impl Fn(usize) -> Option<usize>) -> Validation {
+        // Change of every stack's height since the start
+        let mut change = vec![0i64; self.stacks.len()];
+        let mut required = vec![0u64; self.stacks.len()];
+        let mut invalid = vec![];
+        for (step, m) in self.moves.iter().enumerate() {
+            let (from, to) = (m.from as usize, m.to as usize);
+            if let Some(stack) = [m.from, m.to]
+                .into_iter()
+                .find(|s| *s as usize >= self.stacks.len())
+            {
+                invalid.push(InvalidMove {
+                    step,
+                    line: line(step),
+                    failed: *m,
+                    problem: Problem::UnknownStack(stack),
+                });
+                continue;
+            }
+            let count = m.count as i64;
+            let need = count - change[from];
+            required[from] = required[from].max(need.max(0) as u64);
+
+            let height = self.stacks[from].len() as i64 + change[from];
+            if height < count {
+                invalid.push(InvalidMove {
+                    step,
+                    line: line(step),
+                    failed: *m,
+                    problem: Problem::MissingCrates((count - height.max(0)) as u64),
+                });
+            }
+            change[from] -= count;
+            change[to] += count;
+        }
+        Validation {
+            invalid,
+            required_heights: required,
+        }
+    }
+}
+
+impl ParsedStorage {
+    /// Like `Storage::validate`, reporting the line of every invalid move
+    pub fn validate(&self) -> Validation {
+        self.storage()
+            .validate_with_lines(|step| self.move_line(step))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let validation = Storage::load_with_lines("example.txt").unwrap().validate();
+        assert!(validation.is_valid());
+        assert_eq!(validation.required_heights, vec![2, 3, 0]);
+    }
+
+    #[test]
+    fn input() {
+        let storage = Storage::new("first.txt").unwrap();
+        let validation = storage.validate();
+        assert!(validation.is_valid());
+        for (required, stack) in validation.required_heights.iter().zip(&storage.stacks) {
+            assert!(*required as usize <= stack.len());
+        }
+    }
+
+    #[test]
+    fn invalid_moves() {
+        let input = "[A]\n[B] [C]\n 1   2\n\nmove 1 from 2 to 1\nmove 3 from 1 to 2\nmove 4 from 2 to 1\nmove 5 from 1 to 2";
+        let parsed = Storage::parse_with_lines(input.as_bytes()).unwrap();
+        assert_eq!(parsed.move_line(0), Some(5));
+        let validation = parsed.validate();
+        let storage = parsed.into_storage();
+        assert_eq!(
+            validation.invalid,
+            vec![
+                InvalidMove {
+                    step: 2,
+                    line: Some(7),
+                    failed: storage.moves[2],
+                    problem: Problem::MissingCrates(1)
+                },
+                InvalidMove {
+                    step: 3,
+                    line: Some(8),
+                    failed: storage.moves[3],
+                    problem: Problem::MissingCrates(1)
+                }
+            ]
+        );
+        assert_eq!(validation.required_heights, vec![3, 2]);
+        assert_eq!(storage.stacks[0], ['B', 'A']);
+
+        // Starting with the required heights makes every move succeed
+        let mut fixed = storage.clone();
+        fixed.stacks[0].push_front('D');
+        fixed.stacks[1].push_front('E');
+        assert!(fixed.validate().is_valid());
+        // Without the source lines the same moves are reported
+        let unknown: Vec<_> = storage.validate().invalid.iter().map(|i| i.line).collect();
+        assert_eq!(unknown, vec![None, None]);
+        assert!(fixed.move_crates(&crate::crane::CrateMover9000).is_ok());
+    }
+
+    #[test]
+    fn empty_source() {
+        let storage = Storage::parse(" 1   2\nmove 2 from 1 to 2\nmove 1 from 1 to 2".as_bytes());
+        let validation = storage.unwrap().validate();
+        assert_eq!(validation.invalid[0].line, None);
+        let problems: Vec<_> = validation.invalid.iter().map(|i| i.problem).collect();
+        assert_eq!(
+            problems,
+            vec![Problem::MissingCrates(2), Problem::MissingCrates(1)]
+        );
+        assert_eq!(validation.required_heights, vec![3, 0]);
+    }
+
+    #[test]
+    fn unknown_stacks() {
+        let mut storage = Storage::parse("[A]\n 1   2".as_bytes()).unwrap();
+        storage.moves = [(1, 0, 2), (1, 7, 0), (1, 0, 1), (2, 0, 1)]
+            .into_iter()
+            .map(|(count, from, to)| Move { count, from, to })
+            .collect();
+        let validation = storage.validate();
+        let problems: Vec<_> = validation
+            .invalid
+            .iter()
+            .map(|i| (i.step, i.problem))
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                (0, Problem::UnknownStack(2)),
+                (1, Problem::UnknownStack(7)),
+                (3, Problem::MissingCrates(2))
+            ]
+        );
+        assert_eq!(validation.required_heights, vec![3, 0]);
+    }
+}